@@ -0,0 +1,20 @@
+#!/usr/bin/env python3
+"""Example bot for the stdio bot protocol.
+
+Uses the most expensive card it can afford, otherwise discards the most expensive one.
+Run the game with `--bot-second "python3 bots/most_expensive.py"` to play against it.
+"""
+import json
+import sys
+
+for line in sys.stdin:
+    observation = json.loads(line)
+    hand = observation["hand"]
+    playable = observation["playable"]
+    if playable:
+        best = max(playable, key=lambda i: hand[i]["cost_amount"])
+        answer = {"use": best}
+    else:
+        worst = max(range(len(hand)), key=lambda i: hand[i]["cost_amount"])
+        answer = {"discard": worst}
+    print(json.dumps(answer), flush=True)
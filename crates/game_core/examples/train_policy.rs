@@ -0,0 +1,294 @@
+//! Trains the network used by the `Trained` AI by imitating a teacher policy in the
+//! [`Env`] and writes its weights, run from the workspace root:
+//! `cargo run -p game_core --release --example train_policy` (or `just train_ai`)
+//!
+//! With the default arguments it regenerates `crates/game_core/src/ai/policy.weights.json`:
+//! 2000 games of the Normal teacher against Greedy with the Classic rules and `assets/all.deck.json`,
+//! game `n` uses seed `n` and the agent takes the second seat in odd games, the initial
+//! weights and the sample order come from seed 0. The run is deterministic, so the file only
+//! changes when the deck, the rules or the observation change. Retrain after changing any of
+//! them, it takes about 10 minutes.
+use game_core::ai::{AiDifficulty, Greedy, Layer, PolicyWeights};
+use game_core::data::card::Card;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
+use game_core::env::{self, ACTION_COUNT, ActionMask, Env, OBSERVATION_SIZE, Observation};
+use game_core::rng::{self, RngStream};
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+const HELP: &str = "Usage: train_policy [--games <n>] [--teacher <difficulty>] [--epochs <n>] [--hidden <n>] [--out <file>]";
+
+struct Sample {
+    obs: Observation,
+    mask: ActionMask,
+    action: usize,
+}
+
+struct Args {
+    games: u64,
+    teacher: AiDifficulty,
+    epochs: usize,
+    hidden: usize,
+    out: String,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        games: 2000,
+        teacher: AiDifficulty::Normal,
+        epochs: 40,
+        hidden: 128,
+        out: "crates/game_core/src/ai/policy.weights.json".to_owned(),
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--games" => args.games = value()?.parse().map_err(|_| HELP)?,
+            "--epochs" => args.epochs = value()?.parse().map_err(|_| HELP)?,
+            "--hidden" => args.hidden = value()?.parse().map_err(|_| HELP)?,
+            "--out" => args.out = value()?,
+            "--teacher" => {
+                let name = value()?;
+                args.teacher = AiDifficulty::ALL
+                    .into_iter()
+                    .find(|d| d.to_string().eq_ignore_ascii_case(&name))
+                    .ok_or(format!("Unknown difficulty `{}`", name))?;
+            }
+            _ => return Err(HELP.to_owned()),
+        }
+    }
+    Ok(args)
+}
+
+/// Plays the teacher against the Normal AI and records every teacher decision.
+fn collect(args: &Args, cards: &[Card]) -> Vec<Sample> {
+    let mut env = Env::new(cards.to_vec().into(), Ruleset::default(), Box::new(Greedy));
+    let mut teacher = args.teacher.policy();
+    let mut samples = Vec::new();
+    for seed in 0..args.games {
+        let mut rng = rng::seeded(seed, RngStream::Ai);
+        env.me = PlayerNumber::ALL[seed as usize % 2];
+        let mut obs = env.reset(seed);
+        while !env.is_done() {
+            let view = env.state().view(env.me);
+            let Some(action) = teacher.select_action(&view, &mut rng) else {
+                break;
+            };
+            let action = env::action_index(&view, &action).expect("Teacher picked a card in hand");
+            samples.push(Sample {
+                obs,
+                mask: env.legal_mask(),
+                action,
+            });
+            obs = env
+                .step(action)
+                .expect("Teacher action is legal")
+                .observation;
+        }
+    }
+    samples
+}
+
+/// Dense layer with its Adam state.
+struct Trainable {
+    weights: Vec<f32>,
+    bias: Vec<f32>,
+    inputs: usize,
+    moments: [Vec<f32>; 4],
+}
+
+impl Trainable {
+    fn new(inputs: usize, outputs: usize, rng: &mut impl Rng) -> Self {
+        let limit = (6.0 / (inputs + outputs) as f32).sqrt();
+        Self {
+            weights: (0..inputs * outputs)
+                .map(|_| rng.gen_range(-limit..limit))
+                .collect(),
+            bias: vec![0.0; outputs],
+            inputs,
+            moments: [
+                vec![0.0; inputs * outputs],
+                vec![0.0; inputs * outputs],
+                vec![0.0; outputs],
+                vec![0.0; outputs],
+            ],
+        }
+    }
+
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.bias
+            .iter()
+            .enumerate()
+            .map(|(o, b)| {
+                let row = &self.weights[o * self.inputs..(o + 1) * self.inputs];
+                row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + b
+            })
+            .collect()
+    }
+
+    /// Adds the gradients for one sample, returns the gradient of the input.
+    fn backward(&self, input: &[f32], grad: &[f32], acc: &mut (Vec<f32>, Vec<f32>)) -> Vec<f32> {
+        let mut input_grad = vec![0.0; self.inputs];
+        for (o, g) in grad.iter().enumerate() {
+            acc.1[o] += g;
+            let row = o * self.inputs;
+            for i in 0..self.inputs {
+                acc.0[row + i] += g * input[i];
+                input_grad[i] += g * self.weights[row + i];
+            }
+        }
+        input_grad
+    }
+
+    fn adam(&mut self, acc: &(Vec<f32>, Vec<f32>), step: i32, rate: f32) {
+        let [mw, vw, mb, vb] = &mut self.moments;
+        adam(&mut self.weights, &acc.0, mw, vw, step, rate);
+        adam(&mut self.bias, &acc.1, mb, vb, step, rate);
+    }
+
+    fn to_layer(&self) -> Layer {
+        Layer {
+            weights: self
+                .weights
+                .chunks(self.inputs)
+                .map(|row| row.iter().copied().map(round).collect())
+                .collect(),
+            bias: self.bias.iter().copied().map(round).collect(),
+        }
+    }
+}
+
+/// Keeps the weights file small, the lost precision doesn't change the picked actions.
+fn round(value: f32) -> f32 {
+    (value * 10000.0).round() / 10000.0
+}
+
+fn adam(params: &mut [f32], grad: &[f32], m: &mut [f32], v: &mut [f32], step: i32, rate: f32) {
+    let (b1, b2) = (0.9f32, 0.999f32);
+    for i in 0..params.len() {
+        m[i] = b1 * m[i] + (1.0 - b1) * grad[i];
+        v[i] = b2 * v[i] + (1.0 - b2) * grad[i] * grad[i];
+        let m_hat = m[i] / (1.0 - b1.powi(step));
+        let v_hat = v[i] / (1.0 - b2.powi(step));
+        params[i] -= rate * m_hat / (v_hat.sqrt() + 1e-8);
+    }
+}
+
+/// Softmax over the legal actions, returns the gradient of the cross entropy loss.
+fn loss_grad(scores: &[f32], mask: &ActionMask, target: usize) -> (Vec<f32>, bool) {
+    let max = scores
+        .iter()
+        .zip(mask)
+        .filter(|(_, legal)| **legal)
+        .map(|(s, _)| *s)
+        .fold(f32::MIN, f32::max);
+    let exp: Vec<f32> = scores
+        .iter()
+        .zip(mask)
+        .map(|(s, legal)| if *legal { (s - max).exp() } else { 0.0 })
+        .collect();
+    let sum: f32 = exp.iter().sum();
+    let best = (0..ACTION_COUNT)
+        .filter(|i| mask[*i])
+        .max_by(|a, b| scores[*a].total_cmp(&scores[*b]));
+    let grad = exp
+        .iter()
+        .enumerate()
+        .map(|(i, e)| e / sum - if i == target { 1.0 } else { 0.0 })
+        .collect();
+    (grad, best == Some(target))
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            std::process::exit(1);
+        }
+    };
+    let cards: Vec<Card> = serde_json::from_str(
+        &std::fs::read_to_string("assets/all.deck.json").expect("Run from the workspace root"),
+    )
+    .expect("Invalid deck");
+    let mut samples = collect(&args, &cards);
+    println!("Collected {} decisions", samples.len());
+
+    let mut input_scale = vec![1.0f32; OBSERVATION_SIZE];
+    for sample in &samples {
+        for (scale, x) in input_scale.iter_mut().zip(sample.obs) {
+            *scale = scale.max(x.abs());
+        }
+    }
+    let mut rng = rng::seeded(0, RngStream::Ai);
+    let mut layers = [
+        Trainable::new(OBSERVATION_SIZE, args.hidden, &mut rng),
+        Trainable::new(args.hidden, args.hidden, &mut rng),
+        Trainable::new(args.hidden, ACTION_COUNT, &mut rng),
+    ];
+    let batch = 64;
+    let mut step = 0;
+    for epoch in 0..args.epochs {
+        samples.shuffle(&mut rng);
+        let mut matched = 0;
+        for chunk in samples.chunks(batch) {
+            let mut acc: Vec<_> = layers
+                .iter()
+                .map(|l| (vec![0.0; l.weights.len()], vec![0.0; l.bias.len()]))
+                .collect();
+            for sample in chunk {
+                let input: Vec<f32> = sample
+                    .obs
+                    .iter()
+                    .zip(&input_scale)
+                    .map(|(x, s)| x / s)
+                    .collect();
+                let mut activations = vec![input];
+                for (i, layer) in layers.iter().enumerate() {
+                    let mut out = layer.forward(activations.last().expect("Input is there"));
+                    if i + 1 < layers.len() {
+                        out.iter_mut().for_each(|v| *v = v.max(0.0));
+                    }
+                    activations.push(out);
+                }
+                let (mut grad, hit) = loss_grad(
+                    activations.last().expect("Output is there"),
+                    &sample.mask,
+                    sample.action,
+                );
+                matched += hit as usize;
+                for i in (0..layers.len()).rev() {
+                    if i + 1 < layers.len() {
+                        for (g, a) in grad.iter_mut().zip(&activations[i + 1]) {
+                            if *a <= 0.0 {
+                                *g = 0.0;
+                            }
+                        }
+                    }
+                    grad = layers[i].backward(&activations[i], &grad, &mut acc[i]);
+                }
+            }
+            step += 1;
+            for (layer, acc) in layers.iter_mut().zip(&mut acc) {
+                acc.0.iter_mut().for_each(|g| *g /= chunk.len() as f32);
+                acc.1.iter_mut().for_each(|g| *g /= chunk.len() as f32);
+                layer.adam(acc, step, 0.001);
+            }
+        }
+        println!(
+            "Epoch {}: {:.1}% of the teacher actions matched",
+            epoch + 1,
+            matched as f32 * 100.0 / samples.len() as f32
+        );
+    }
+
+    let weights = PolicyWeights {
+        input_scale,
+        layers: layers.iter().map(Trainable::to_layer).collect(),
+    };
+    let json = serde_json::to_string(&weights).expect("Weights are serializable");
+    std::fs::write(&args.out, json).expect("Failed to write the weights");
+    println!("Weights written to {}", args.out);
+}
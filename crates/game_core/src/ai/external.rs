@@ -0,0 +1,264 @@
+//! Policy asking an external program for every action, so bots can be written in any language.
+//!
+//! The program is started once per match. Every turn it gets one line of JSON with the
+//! [`Observation`] on its stdin and has to answer with one line on its stdout:
+//! `{"use": 2}` to use the card at that index of the hand or `{"discard": 0}` to drop it.
+//! Anything written to stderr is passed through, so it can be used for logging.
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use super::{AiPolicy, RandomLegal};
+use crate::data::{
+    hand::HandCards,
+    player::{PlayerHealth, PlayerNumber},
+    supply::PlayerSupply,
+};
+use crate::r#match::{ActionTaken, MatchView};
+use crate::rng::GameRng;
+
+/// Bump when the observation or the answer change in a way older bots can't read.
+pub const BOT_PROTOCOL_VERSION: u32 = 1;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Everything the bot knows about the match when it has to pick the action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Observation {
+    pub version: u32,
+    pub me: PlayerNumber,
+    pub turn: u32,
+    pub supply: PlayerSupply,
+    pub health: PlayerHealth,
+    pub hand: HandCards,
+    /// Indexes of the hand cards the player can afford
+    pub playable: Vec<usize>,
+    pub enemy_supply: PlayerSupply,
+    pub enemy_health: PlayerHealth,
+    pub enemy_hand_size: usize,
+}
+
+impl Observation {
+    pub fn new(view: &MatchView) -> Self {
+        Self {
+            version: BOT_PROTOCOL_VERSION,
+            me: view.me,
+            turn: view.turn,
+            supply: view.supply.clone(),
+            health: *view.health,
+            hand: view.hand.clone(),
+            playable: (0..view.hand.len())
+                .filter(|i| view.supply.can_afford_card(&view.hand[*i]))
+                .collect(),
+            enemy_supply: view.enemy_supply.clone(),
+            enemy_health: *view.enemy_health,
+            enemy_hand_size: view.enemy_hand_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotAnswer {
+    Use(usize),
+    Discard(usize),
+}
+
+impl BotAnswer {
+    /// Turns the answer into the action, `None` when it can't be performed.
+    pub fn to_action(self, view: &MatchView) -> Option<ActionTaken> {
+        match self {
+            BotAnswer::Use(i) => view
+                .hand
+                .get(i)
+                .filter(|card| view.supply.can_afford_card(card))
+                .map(|card| ActionTaken::UseCard { card: card.clone() }),
+            BotAnswer::Discard(i) => view
+                .hand
+                .get(i)
+                .map(|card| ActionTaken::DropCard { card: card.clone() }),
+        }
+    }
+}
+
+/// Bot running as a child process, see the module docs for the protocol.
+pub struct ExternalBot {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    answers: Mutex<Receiver<String>>,
+    /// Time the bot has to answer
+    pub timeout: Duration,
+    /// Turns in which the bot failed to answer with a legal action,
+    /// a random legal action is used instead.
+    pub failures: u32,
+}
+
+impl ExternalBot {
+    /// Starts the program, `command` is split on whitespace into the program and its arguments.
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Empty bot command"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("Stdin is piped");
+        let stdout = child.stdout.take().expect("Stdout is piped");
+        let (sender, answers) = mpsc::channel();
+        // reading on another thread, so a stuck bot can't block the match
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            command: command.to_owned(),
+            child,
+            stdin,
+            answers: Mutex::new(answers),
+            timeout: DEFAULT_TIMEOUT,
+            failures: 0,
+        })
+    }
+
+    fn ask(&mut self, view: &MatchView) -> Result<ActionTaken, String> {
+        let answers = self.answers.get_mut().map_err(|e| e.to_string())?;
+        // answers that came after the timeout are not for this turn
+        while answers.try_recv().is_ok() {}
+        let observation =
+            serde_json::to_string(&Observation::new(view)).map_err(|e| e.to_string())?;
+        writeln!(self.stdin, "{}", observation)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| e.to_string())?;
+        let line = answers
+            .recv_timeout(self.timeout)
+            .map_err(|e| e.to_string())?;
+        let answer: BotAnswer = serde_json::from_str(line.trim()).map_err(|e| e.to_string())?;
+        answer
+            .to_action(view)
+            .ok_or_else(|| format!("{:?} is not a legal action", answer))
+    }
+}
+
+impl AiPolicy for ExternalBot {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        match self.ask(view) {
+            Ok(action) => Some(action),
+            Err(err) => {
+                self.failures += 1;
+                log::warn!("Bot `{}` failed: {}", self.command, err);
+                RandomLegal.select_action(view, rng)
+            }
+        }
+    }
+}
+
+impl Drop for ExternalBot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::tests::new_match;
+    use crate::data::resource::ResourceType;
+
+    #[test]
+    fn answer_with_index_out_of_hand_is_rejected() {
+        let state = new_match(1);
+        let view = state.view(state.current);
+        let size = view.hand.len();
+
+        assert_eq!(BotAnswer::Use(size).to_action(&view), None);
+        assert_eq!(BotAnswer::Discard(size).to_action(&view), None);
+        assert_eq!(
+            BotAnswer::Discard(0).to_action(&view),
+            Some(ActionTaken::DropCard {
+                card: view.hand[0].clone()
+            })
+        );
+    }
+
+    #[test]
+    fn unaffordable_card_can_only_be_discarded() {
+        let mut state = new_match(1);
+        let card = &mut state.current_player_mut().hand[0];
+        card.cost_resource = ResourceType::Magic;
+        card.cost_amount = 100;
+        let view = state.view(state.current);
+
+        assert!(!Observation::new(&view).playable.contains(&0));
+        assert_eq!(BotAnswer::Use(0).to_action(&view), None);
+        assert!(BotAnswer::Discard(0).to_action(&view).is_some());
+    }
+
+    #[test]
+    fn answers_are_read_from_snake_case_json() {
+        assert_eq!(
+            serde_json::from_str::<BotAnswer>(r#"{"use": 2}"#).unwrap(),
+            BotAnswer::Use(2)
+        );
+        assert_eq!(
+            serde_json::from_str::<BotAnswer>(r#"{"discard": 0}"#).unwrap(),
+            BotAnswer::Discard(0)
+        );
+    }
+
+    /// Bots written against the documented format break when this changes,
+    /// [`BOT_PROTOCOL_VERSION`] has to be bumped together with the expected keys.
+    #[test]
+    fn observation_json_shape() {
+        assert_eq!(BOT_PROTOCOL_VERSION, 1);
+        let state = new_match(1);
+        let json = serde_json::to_value(Observation::new(&state.view(state.current))).unwrap();
+        let observation = json.as_object().unwrap();
+
+        let mut keys: Vec<&str> = observation.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "enemy_hand_size",
+                "enemy_health",
+                "enemy_supply",
+                "hand",
+                "health",
+                "me",
+                "playable",
+                "supply",
+                "turn",
+                "version",
+            ]
+        );
+        assert_eq!(observation["version"], BOT_PROTOCOL_VERSION);
+        assert_eq!(observation["me"], "First");
+        assert!(observation["hand"].is_array());
+        assert!(observation["playable"].is_array());
+        for key in ["health", "enemy_health"] {
+            assert!(observation[key]["tower_hp"].is_i64());
+            assert!(observation[key]["walls_hp"].is_i64());
+        }
+        for key in ["supply", "enemy_supply"] {
+            for resource in ["tools", "magic", "soldiers"] {
+                assert!(observation[key][resource]["amount"].is_i64());
+                assert!(observation[key][resource]["production"].is_i64());
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::{AiPolicy, evaluate};
+use crate::r#match::{ActionTaken, MatchView};
+use crate::rng::GameRng;
+
+/// Picks the action leading to the best evaluated position after one move.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Greedy;
+
+impl AiPolicy for Greedy {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        let state = view.determinize(rng);
+        let mut best: Option<(f32, ActionTaken)> = None;
+        for action in view.legal_actions() {
+            let mut next = state.clone();
+            if next.apply(&action).is_err() {
+                continue;
+            }
+            let score = evaluate(&next, view.me) + discard_bias(view, &action);
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, _)| score > *best_score)
+            {
+                best = Some((score, action));
+            }
+        }
+        best.map(|(_, action)| action)
+    }
+}
+
+/// Prefers discarding cards that are the furthest from being affordable.
+pub(super) fn discard_bias(view: &MatchView, action: &ActionTaken) -> f32 {
+    match action {
+        ActionTaken::UseCard { .. } => 0.0,
+        ActionTaken::DropCard { card } => {
+            let missing = card.cost_amount - view.supply.get(card.cost_resource).amount;
+            -1.0 + missing as f32 * 0.05
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::greedy::discard_bias;
+use super::{AiPolicy, evaluate};
+use crate::data::player::PlayerNumber;
+use crate::r#match::{ActionTaken, MatchState, MatchView};
+use crate::rng::GameRng;
+
+/// Minimax search over own actions and opponent replies,
+/// averaged over a few guesses of the opponent hand.
+#[derive(Debug, Clone, Copy)]
+pub struct Lookahead {
+    /// Amount of moves checked ahead, including the one being picked
+    pub depth: u32,
+    /// Amount of guessed opponent hands
+    pub samples: usize,
+}
+
+impl Default for Lookahead {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            samples: 8,
+        }
+    }
+}
+
+impl AiPolicy for Lookahead {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        let samples: Vec<_> = (0..self.samples.max(1))
+            .map(|_| view.determinize(rng))
+            .collect();
+        let mut best: Option<(f32, ActionTaken)> = None;
+        for action in view.legal_actions() {
+            let mut total = 0.0;
+            for state in samples.iter() {
+                let mut next = state.clone();
+                if next.apply(&action).is_ok() {
+                    total += search(&next, view.me, self.depth.saturating_sub(1));
+                }
+            }
+            let score = total / samples.len() as f32 + discard_bias(view, &action);
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, _)| score > *best_score)
+            {
+                best = Some((score, action));
+            }
+        }
+        best.map(|(_, action)| action)
+    }
+}
+
+fn search(state: &MatchState, me: PlayerNumber, depth: u32) -> f32 {
+    if depth == 0 || state.is_finished() {
+        return evaluate(state, me);
+    }
+    let scores = state.legal_actions().into_iter().filter_map(|action| {
+        let mut next = state.clone();
+        next.apply(&action).ok()?;
+        Some(search(&next, me, depth - 1))
+    });
+    if state.current == me {
+        scores.fold(f32::MIN, f32::max)
+    } else {
+        scores.fold(f32::MAX, f32::min)
+    }
+}
@@ -0,0 +1,203 @@
+//! Computer controlled opponents.
+//!
+//! Every [`AiPolicy`] only gets the [`MatchView`] of its player, hidden
+//! information has to be guessed the same way a human player would.
+#[cfg(feature = "bevy")]
+use bevy::{
+    prelude::{Component, Resource},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::data::player::PlayerNumber;
+use crate::r#match::{ActionTaken, MatchState, MatchView};
+use crate::rng::GameRng;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod external;
+mod greedy;
+mod lookahead;
+mod monte_carlo;
+mod random;
+mod trained;
+
+pub use greedy::Greedy;
+pub use lookahead::Lookahead;
+pub use monte_carlo::{Budget, MonteCarlo, card_value};
+pub use random::RandomLegal;
+pub use trained::{Layer, PolicyWeights, Trained};
+
+pub trait AiPolicy: Send + Sync {
+    /// Picks the action for the player owning the `view`, it has to be that player turn.
+    /// Returns `None` only when the hand is empty, which needs a deck without cards.
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken>;
+}
+
+/// Component driving a player with the boxed policy.
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct AiPlayer(pub Box<dyn AiPolicy>);
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Copy, Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(Resource, Reflect))]
+pub enum AiDifficulty {
+    /// Plays random cards it can afford
+    Easy,
+    /// Plays the card that gives the best position right away
+    #[default]
+    Normal,
+    /// Takes into account the best response of the opponent
+    Hard,
+    /// Simulates many possible continuations of the match
+    Expert,
+    /// Network trained offline on the game environment
+    Trained,
+}
+
+impl AiDifficulty {
+    pub const ALL: [Self; 5] = [
+        Self::Easy,
+        Self::Normal,
+        Self::Hard,
+        Self::Expert,
+        Self::Trained,
+    ];
+
+    pub fn policy(self) -> Box<dyn AiPolicy> {
+        match self {
+            AiDifficulty::Easy => Box::new(RandomLegal),
+            AiDifficulty::Normal => Box::new(Greedy),
+            AiDifficulty::Hard => Box::<Lookahead>::default(),
+            AiDifficulty::Expert => Box::<MonteCarlo>::default(),
+            AiDifficulty::Trained => Box::<Trained>::default(),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|d| *d == self)
+            .unwrap_or_default();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for AiDifficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Scores the match from the perspective of `me`, the higher the better.
+pub fn evaluate(state: &MatchState, me: PlayerNumber) -> f32 {
+    if let Some(outcome) = state.outcome() {
+        return if outcome.winner == me {
+            1000.0
+        } else {
+            -1000.0
+        };
+    }
+    let score = |nr: PlayerNumber| {
+        let player = state.player(nr);
+        let supply = &player.supply;
+        let production =
+            supply.tools.production + supply.magic.production + supply.soldiers.production;
+        let amount = supply.tools.amount + supply.magic.amount + supply.soldiers.amount;
+        let tower = player.health.tower_hp as f32;
+        // closing in on either win condition is worth more than the raw hp
+        let margin = state.rules.base_walls_hp;
+        let urgency = if player.health.tower_hp < margin
+            || state.rules.max_tower_hp - player.health.tower_hp < margin
+        {
+            2.0
+        } else {
+            1.0
+        };
+        tower * 2.0 * urgency
+            + player.health.walls_hp as f32
+            + production as f32 * 3.0
+            + amount as f32 * 0.3
+    };
+    score(me) - score(me.other())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{card::Card, deck::Deck, ruleset::Ruleset};
+    use crate::rng::{self, RngStream};
+
+    /// Match played with the cards from the game assets.
+    pub(crate) fn new_match(seed: u64) -> MatchState {
+        let cards: Vec<Card> =
+            serde_json::from_str(include_str!("../../../../assets/all.deck.json")).unwrap();
+        let deck = Deck::from(cards);
+        MatchState::new([deck.clone(), deck], seed, Ruleset::default())
+    }
+
+    fn assert_plays_valid_actions(mut policy: impl AiPolicy) {
+        for seed in 0..4 {
+            let mut state = new_match(seed);
+            let mut rng = rng::seeded(seed, RngStream::Ai);
+            while !state.is_finished() && state.turn < 40 {
+                let action = policy
+                    .select_action(&state.view(state.current), &mut rng)
+                    .expect("Hand is not empty");
+                assert!(
+                    state.validate(&action).is_ok(),
+                    "seed {} turn {}: {:?}",
+                    seed,
+                    state.turn,
+                    action
+                );
+                state.apply(&action).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn random_legal_plays_valid_actions() {
+        assert_plays_valid_actions(RandomLegal);
+    }
+
+    #[test]
+    fn greedy_plays_valid_actions() {
+        assert_plays_valid_actions(Greedy);
+    }
+
+    #[test]
+    fn lookahead_plays_valid_actions() {
+        assert_plays_valid_actions(Lookahead {
+            depth: 2,
+            samples: 2,
+        });
+    }
+
+    #[test]
+    fn monte_carlo_plays_valid_actions() {
+        assert_plays_valid_actions(MonteCarlo {
+            budget: Budget {
+                iterations: 50,
+                time: None,
+            },
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn trained_plays_valid_actions() {
+        assert_plays_valid_actions(Trained::default());
+    }
+
+    #[test]
+    fn empty_hand_has_no_action() {
+        let mut state = new_match(1);
+        let nr = state.current;
+        state.player_mut(nr).hand.clear();
+        let view = state.view(nr);
+        let mut rng = rng::seeded(1, RngStream::Ai);
+        for mut policy in AiDifficulty::ALL.map(AiDifficulty::policy) {
+            assert_eq!(policy.select_action(&view, &mut rng), None);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use rand::Rng;
+use std::time::Duration;
+use web_time::Instant;
+
+use super::{AiPolicy, evaluate};
+use crate::data::card::Card;
+use crate::data::player::PlayerNumber;
+use crate::data::resource::ResourceType;
+use crate::data::supply::PlayerSupply;
+use crate::r#match::{ActionTaken, MatchState, MatchView};
+use crate::rng::GameRng;
+
+/// Limits for the amount of work done for a single decision,
+/// whichever is reached first ends the search.
+/// Every legal action still gets one playout.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub iterations: u32,
+    pub time: Option<Duration>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            iterations: 3000,
+            time: Some(Duration::from_millis(300)),
+        }
+    }
+}
+
+/// Plays many short random games for every legal action, each time with
+/// a freshly guessed opponent hand, and picks the one that scores best.
+/// Root actions are picked with UCB1 so promising ones get more playouts.
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarlo {
+    pub budget: Budget,
+    /// Max amount of moves in a single playout before evaluating the position
+    pub playout_depth: u32,
+}
+
+impl Default for MonteCarlo {
+    fn default() -> Self {
+        Self {
+            budget: Budget::default(),
+            playout_depth: 4,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct ActionStats {
+    visits: u32,
+    total: f32,
+}
+
+impl ActionStats {
+    fn mean(&self) -> f32 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total / self.visits as f32
+        }
+    }
+}
+
+impl AiPolicy for MonteCarlo {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        let actions = view.legal_actions();
+        let stats = self.search(view, &actions, rng);
+        stats
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.mean().total_cmp(&b.mean()))
+            .map(|(i, _)| actions[i].clone())
+    }
+}
+
+impl MonteCarlo {
+    /// Playout results of every action, each one is tried at least once.
+    fn search(
+        &self,
+        view: &MatchView,
+        actions: &[ActionTaken],
+        rng: &mut GameRng,
+    ) -> Vec<ActionStats> {
+        let mut stats = vec![ActionStats::default(); actions.len()];
+        if actions.is_empty() {
+            return stats;
+        }
+        let start = Instant::now();
+
+        for iteration in 0..self.budget.iterations.max(actions.len() as u32) {
+            if self
+                .budget
+                .time
+                .is_some_and(|limit| iteration as usize >= actions.len() && start.elapsed() > limit)
+            {
+                break;
+            }
+            let i = select_ucb(&stats, iteration);
+            let mut state = view.determinize(rng);
+            let score = match state.apply(&actions[i]) {
+                Ok(_) => self.playout(&mut state, view.me, rng),
+                Err(_) => 0.0,
+            };
+            stats[i].visits += 1;
+            stats[i].total += score;
+        }
+        stats
+    }
+
+    /// Returns a score between 0 (lost) and 1 (won).
+    fn playout(&self, state: &mut MatchState, me: PlayerNumber, rng: &mut GameRng) -> f32 {
+        for _ in 0..self.playout_depth {
+            if state.is_finished() {
+                break;
+            }
+            let Some(action) = playout_action(state, rng) else {
+                break;
+            };
+            if state.apply(&action).is_err() {
+                break;
+            }
+        }
+        // squash the evaluation so a single huge swing does not dominate the average
+        let score = evaluate(state, me);
+        1.0 / (1.0 + (-score / 20.0).exp())
+    }
+}
+
+fn select_ucb(stats: &[ActionStats], iteration: u32) -> usize {
+    if let Some(unvisited) = stats.iter().position(|s| s.visits == 0) {
+        return unvisited;
+    }
+    let ln_total = (iteration.max(1) as f32).ln();
+    stats
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            let ucb = |s: &ActionStats| s.mean() + (2.0 * ln_total / s.visits as f32).sqrt();
+            ucb(a).total_cmp(&ucb(b))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or_default()
+}
+
+/// Cheap move used inside playouts: mostly the best looking affordable card,
+/// otherwise discards the least useful one.
+fn playout_action(state: &MatchState, rng: &mut GameRng) -> Option<ActionTaken> {
+    let player = state.current_player();
+    let noise = |rng: &mut GameRng| rng.gen_range(0.0..1.0);
+    let best_use = player
+        .hand
+        .iter()
+        .filter(|c| player.supply.can_afford_card(c))
+        .map(|c| (card_value(c, &player.supply) + noise(rng), c))
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some((_, card)) = best_use {
+        return Some(ActionTaken::UseCard { card: card.clone() });
+    }
+    player
+        .hand
+        .iter()
+        .map(|c| (card_value(c, &player.supply) + noise(rng), c))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, c)| ActionTaken::DropCard { card: c.clone() })
+}
+
+/// Rough worth of playing the card, based only on the card itself.
+pub fn card_value(card: &Card, supply: &PlayerSupply) -> f32 {
+    let (damage, _) = card.damage(false);
+    let (self_damage, _) = card.damage(true);
+    let (mut production, mut amount) = (0, 0);
+    for resource in ResourceType::ALL {
+        production +=
+            card.production_change(resource, true) - card.production_change(resource, false);
+        // losing resources we don't have costs nothing
+        amount += card
+            .resource_amount_change(resource, true)
+            .max(-supply.get(resource).amount)
+            - card.resource_amount_change(resource, false);
+    }
+
+    damage as f32 - self_damage as f32 + card.tower_growth(true) as f32 * 1.5
+        - card.tower_growth(false) as f32 * 1.5
+        + card.walls_growth(true) as f32
+        - card.walls_growth(false) as f32
+        + production as f32 * 4.0
+        + amount as f32 * 0.3
+        - card.cost_amount as f32 * 0.2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::tests::new_match;
+    use crate::rng::{self, RngStream};
+
+    fn playouts(budget: Budget) -> (u32, usize) {
+        let state = new_match(2);
+        let view = state.view(state.current);
+        let actions = view.legal_actions();
+        let policy = MonteCarlo {
+            budget,
+            ..Default::default()
+        };
+        let stats = policy.search(&view, &actions, &mut rng::seeded(2, RngStream::Ai));
+        (stats.iter().map(|s| s.visits).sum(), actions.len())
+    }
+
+    #[test]
+    fn search_stops_at_iteration_budget() {
+        let (visits, _) = playouts(Budget {
+            iterations: 100,
+            time: None,
+        });
+        assert_eq!(visits, 100);
+    }
+
+    #[test]
+    fn search_tries_every_action_once() {
+        let (visits, actions) = playouts(Budget {
+            iterations: 1,
+            time: Some(Duration::ZERO),
+        });
+        assert_eq!(visits as usize, actions);
+    }
+}
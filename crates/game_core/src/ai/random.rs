@@ -0,0 +1,27 @@
+use rand::seq::SliceRandom;
+
+use super::AiPolicy;
+use crate::r#match::{ActionTaken, MatchView};
+use crate::rng::GameRng;
+
+/// Uses a random affordable card, discards a random one when nothing can be afforded.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomLegal;
+
+impl AiPolicy for RandomLegal {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        let affordable: Vec<_> = view
+            .hand
+            .iter()
+            .filter(|c| view.supply.can_afford_card(c))
+            .collect();
+        if let Some(card) = affordable.choose(rng) {
+            return Some(ActionTaken::UseCard {
+                card: (*card).clone(),
+            });
+        }
+        view.hand
+            .choose(rng)
+            .map(|card| ActionTaken::DropCard { card: card.clone() })
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+
+use super::{AiPolicy, RandomLegal};
+use crate::env::{self, ACTION_COUNT, OBSERVATION_SIZE, Observation};
+use crate::r#match::{ActionTaken, MatchView};
+use crate::rng::GameRng;
+
+/// Weights shipped with the game, made by the `train_policy` example with its default arguments.
+const DEFAULT_WEIGHTS: &str = include_str!("policy.weights.json");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Layer {
+    /// One row of input weights for every output
+    pub weights: Vec<Vec<f32>>,
+    pub bias: Vec<f32>,
+}
+
+impl Layer {
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weights
+            .iter()
+            .zip(&self.bias)
+            .map(|(row, bias)| row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + bias)
+            .collect()
+    }
+}
+
+/// Fully connected network with ReLU between the layers,
+/// maps the [`Observation`] to a score for every action index.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PolicyWeights {
+    /// Observation values are divided by these before the first layer
+    pub input_scale: Vec<f32>,
+    pub layers: Vec<Layer>,
+}
+
+impl PolicyWeights {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Checks the sizes of the layers against the observation and the action count.
+    pub fn is_valid(&self) -> bool {
+        let mut size = OBSERVATION_SIZE;
+        for layer in &self.layers {
+            if layer.bias.len() != layer.weights.len()
+                || layer.weights.iter().any(|row| row.len() != size)
+            {
+                return false;
+            }
+            size = layer.weights.len();
+        }
+        self.input_scale.len() == OBSERVATION_SIZE && size == ACTION_COUNT
+    }
+
+    pub fn forward(&self, obs: &Observation) -> Vec<f32> {
+        let mut values: Vec<f32> = obs
+            .iter()
+            .zip(&self.input_scale)
+            .map(|(x, scale)| x / scale)
+            .collect();
+        for (i, layer) in self.layers.iter().enumerate() {
+            values = layer.forward(&values);
+            if i + 1 < self.layers.len() {
+                values.iter_mut().for_each(|v| *v = v.max(0.0));
+            }
+        }
+        values
+    }
+}
+
+/// Network trained offline on the [`crate::env`] environment,
+/// uses the legal action with the highest score.
+#[derive(Debug, Clone)]
+pub struct Trained {
+    pub weights: Arc<PolicyWeights>,
+}
+
+impl Default for Trained {
+    fn default() -> Self {
+        static WEIGHTS: OnceLock<Arc<PolicyWeights>> = OnceLock::new();
+        let weights = WEIGHTS.get_or_init(|| {
+            Arc::new(PolicyWeights::from_json(DEFAULT_WEIGHTS).expect("Invalid default weights"))
+        });
+        Self {
+            weights: weights.clone(),
+        }
+    }
+}
+
+impl AiPolicy for Trained {
+    fn select_action(&mut self, view: &MatchView, rng: &mut GameRng) -> Option<ActionTaken> {
+        if !self.weights.is_valid() {
+            return RandomLegal.select_action(view, rng);
+        }
+        let scores = self.weights.forward(&env::observe(view));
+        env::legal_mask(view)
+            .into_iter()
+            .zip(scores)
+            .enumerate()
+            .filter(|(_, (legal, _))| *legal)
+            .max_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+            .and_then(|(i, _)| env::to_action(view, i))
+            .or_else(|| RandomLegal.select_action(view, rng))
+    }
+}
@@ -1,8 +1,3 @@
-pub const CARDS_IN_DECK: i32 = 5;
-pub const BASE_RESOURCE_AMOUNT: i32 = 3;
-pub const BASE_RESOURCE_PRODUCTION: i32 = 1;
-pub const BASE_TOWER_HP: i32 = 15;
-pub const BASE_WALLS_HP: i32 = 5;
-pub const MAX_TOWER_HP: i32 = 50;
-pub const MAX_WALLS_HP: i32 = 30;
-pub const MAX_PRODUCTION: i32 = 10;
+pub const MIN_DECK_SIZE: u32 = 15;
+pub const MAX_DECK_SIZE: u32 = 40;
+pub const MAX_CARD_COPIES: u32 = 3;
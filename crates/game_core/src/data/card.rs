@@ -1,10 +1,11 @@
+use super::player::PlayerNumber;
 use super::resource::ResourceType;
 #[cfg(feature = "bevy")]
 use bevy::reflect::Reflect;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 pub struct Card {
     pub name: String,
@@ -12,9 +13,37 @@ pub struct Card {
     pub cost_amount: i32,
     pub cost_resource: ResourceType,
     pub effects: Vec<CardEffect>,
+    /// How many times the card is put into the draw pile
+    #[serde(default = "one_copy", skip_serializing_if = "is_one_copy")]
+    pub copies: u32,
+}
+
+fn one_copy() -> u32 {
+    1
+}
+
+fn is_one_copy(copies: &u32) -> bool {
+    *copies == 1
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            id: 0,
+            cost_amount: 0,
+            cost_resource: ResourceType::default(),
+            effects: Vec::new(),
+            copies: one_copy(),
+        }
+    }
 }
 
 impl Card {
+    pub fn get_image_asset(&self) -> String {
+        format!("cards/{}.png", self.id)
+    }
+
     pub fn get_sound_asset(&self) -> String {
         format!(
             "snd/card_{}.ogg",
@@ -22,27 +51,31 @@ impl Card {
         )
     }
 
-    pub fn production_change(&self, is_user: bool) -> (ResourceType, i32) {
-        let mut production_change = (ResourceType::Magic, 0i32);
-        for el in self.effects.iter() {
+    /// Sum of production changes of the resource for the card user or the enemy.
+    pub fn production_change(&self, resource: ResourceType, is_user: bool) -> i32 {
+        let mut change = 0i32;
+        for el in self.effects.iter().filter(|e| e.is_flat()) {
             match el.effect_type {
-                EffectType::ProductionChange(effect_type, amount) if el.affects_user == is_user => {
-                    production_change.0 = effect_type;
-                    production_change.1 += amount;
+                EffectType::ProductionChange(res, amount)
+                    if el.target.affects(is_user) && res == resource =>
+                {
+                    change += amount;
                 }
                 _ => {}
             }
         }
-        production_change
+        change
     }
 
-    pub fn resource_amount_change(&self, is_user: bool) -> (ResourceType, i32) {
-        let mut change = (ResourceType::Magic, 0i32);
-        for el in self.effects.iter() {
+    /// Sum of amount changes of the resource for the card user or the enemy.
+    pub fn resource_amount_change(&self, resource: ResourceType, is_user: bool) -> i32 {
+        let mut change = 0i32;
+        for el in self.effects.iter().filter(|e| e.is_flat()) {
             match el.effect_type {
-                EffectType::ResourceChange(effect_type, amount) if el.affects_user == is_user => {
-                    change.0 = effect_type;
-                    change.1 += amount;
+                EffectType::ResourceChange(res, amount)
+                    if el.target.affects(is_user) && res == resource =>
+                {
+                    change += amount;
                 }
                 _ => {}
             }
@@ -52,9 +85,9 @@ impl Card {
 
     pub fn damage(&self, is_user: bool) -> (i32, bool) {
         let mut damage = (0i32, false);
-        for el in self.effects.iter() {
+        for el in self.effects.iter().filter(|e| e.is_flat()) {
             match el.effect_type {
-                EffectType::Damage(amount, ignore_wall) if el.affects_user == is_user => {
+                EffectType::Damage(amount, ignore_wall) if el.target.affects(is_user) => {
                     damage.0 += amount;
                     damage.1 = ignore_wall;
                 }
@@ -66,9 +99,9 @@ impl Card {
 
     pub fn tower_growth(&self, is_user: bool) -> i32 {
         let mut growth = 0i32;
-        for el in self.effects.iter() {
+        for el in self.effects.iter().filter(|e| e.is_flat()) {
             match el.effect_type {
-                EffectType::TowerGrowth(amount) if el.affects_user == is_user => {
+                EffectType::TowerGrowth(amount) if el.target.affects(is_user) => {
                     growth += amount;
                 }
                 _ => {}
@@ -79,9 +112,9 @@ impl Card {
 
     pub fn walls_growth(&self, is_user: bool) -> i32 {
         let mut growth = 0i32;
-        for el in self.effects.iter() {
+        for el in self.effects.iter().filter(|e| e.is_flat()) {
             match el.effect_type {
-                EffectType::WallsGrowth(amount) if el.affects_user == is_user => {
+                EffectType::WallsGrowth(amount) if el.target.affects(is_user) => {
                     growth += amount;
                 }
                 _ => {}
@@ -94,44 +127,240 @@ impl Card {
 #[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Deserialize, Serialize, Default)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 pub enum EffectType {
-    /// dont use multiple production change per user
     ProductionChange(ResourceType, i32),
     /// if true ignores wall
     Damage(i32, bool),
     ResourceChange(ResourceType, i32),
     TowerGrowth(i32),
     WallsGrowth(i32),
+    /// damage equal to the value, if true ignores wall
+    ScaledDamage(Value, bool),
+    SwapWalls,
+    /// moves resources from the affected player to the other one,
+    /// with both players affected each of them steals in turn
+    Steal(ResourceType, i32),
+    /// card user takes another turn
+    PlayAgain,
+    /// affected player discards random cards from the hand
+    DiscardRandom(i32),
     #[default]
     None,
 }
 
+impl EffectType {
+    /// Effects that can't be summed up by the helper methods of [`Card`]
+    /// and are resolved one by one.
+    pub fn is_special(&self) -> bool {
+        matches!(
+            self,
+            EffectType::ScaledDamage(..)
+                | EffectType::SwapWalls
+                | EffectType::Steal(..)
+                | EffectType::PlayAgain
+                | EffectType::DiscardRandom(_)
+        )
+    }
+}
+
+/// Single number describing the player state.
+#[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub enum Stat {
+    Tower,
+    Walls,
+    Amount(ResourceType),
+    Production(ResourceType),
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stat::Tower => write!(f, "Health"),
+            Stat::Walls => write!(f, "Shield"),
+            Stat::Amount(resource) => write!(f, "{}", resource),
+            Stat::Production(resource) => write!(f, "{} production", resource),
+        }
+    }
+}
+
+/// Number used by effects and conditions, stats are read from the card user (`Own`)
+/// or from the other player (`Enemy`) when the effect is resolved.
+#[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub enum Value {
+    Fixed(i32),
+    Own(Stat),
+    Enemy(Stat),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Fixed(v) => write!(f, "{}", v),
+            Value::Own(stat) => write!(f, "your {}", stat),
+            Value::Enemy(stat) => write!(f, "enemy {}", stat),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    pub fn check(&self, left: i32, right: i32) -> bool {
+        match self {
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self {
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Equal => "=",
+        };
+        write!(f, "{}", sign)
+    }
+}
+
+/// Effect is resolved only if `left cmp right` holds, e.g. your Shield > enemy Shield.
+#[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub struct Condition {
+    pub left: Value,
+    pub cmp: Comparison,
+    pub right: Value,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if {} {} {}", self.left, self.cmp, self.right)
+    }
+}
+
+/// Player affected by the effect, from the perspective of the card user.
+#[derive(PartialEq, Eq, Hash, Copy, Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[serde(try_from = "TargetRepr")]
+pub enum Target {
+    User,
+    #[default]
+    Enemy,
+    Both,
+}
+
+/// Decks written before [`Target`] store it as the `affects_user` flag.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TargetRepr {
+    AffectsUser(bool),
+    Name(String),
+}
+
+impl TryFrom<TargetRepr> for Target {
+    type Error = String;
+
+    fn try_from(repr: TargetRepr) -> Result<Self, Self::Error> {
+        match repr {
+            TargetRepr::AffectsUser(true) => Ok(Target::User),
+            TargetRepr::AffectsUser(false) => Ok(Target::Enemy),
+            TargetRepr::Name(name) => match name.as_str() {
+                "User" => Ok(Target::User),
+                "Enemy" => Ok(Target::Enemy),
+                "Both" => Ok(Target::Both),
+                _ => Err(format!("unknown effect target {}", name)),
+            },
+        }
+    }
+}
+
+impl Target {
+    /// Checks if the card user (`is_user`) or the enemy is affected.
+    pub fn affects(self, is_user: bool) -> bool {
+        match self {
+            Target::User => is_user,
+            Target::Enemy => !is_user,
+            Target::Both => true,
+        }
+    }
+
+    /// Affected players when the card is used by `user`.
+    pub fn players(self, user: PlayerNumber) -> impl Iterator<Item = PlayerNumber> {
+        PlayerNumber::ALL
+            .into_iter()
+            .filter(move |nr| self.affects(*nr == user))
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Target::User => "",
+            Target::Enemy => "Enemy ",
+            Target::Both => "Both players ",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 pub struct CardEffect {
-    pub affects_user: bool,
+    #[serde(alias = "affects_user")]
+    pub target: Target,
     pub effect_type: EffectType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
 }
 
 impl CardEffect {
     pub fn empty() -> CardEffect {
         CardEffect {
-            affects_user: true,
+            target: Target::User,
             effect_type: EffectType::None,
+            condition: None,
+        }
+    }
+
+    /// Effect that is always resolved the same way and can be summed up with others.
+    pub fn is_flat(&self) -> bool {
+        self.condition.is_none() && !self.effect_type.is_special()
+    }
+
+    fn damage_prefix(&self) -> &'static str {
+        match self.target {
+            Target::User => "Takes",
+            Target::Enemy => "",
+            Target::Both => "Both take",
         }
     }
 }
 
 impl fmt::Display for CardEffect {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(condition) = self.condition {
+            write!(f, "{}: ", condition)?;
+        }
         let mut result: String = match self.effect_type {
             EffectType::ProductionChange(resource, amount) => {
                 let sign = if amount > 0 { "+" } else { "-" };
-                let target = if self.affects_user { "" } else { "Enemy " };
 
                 return write!(
                     f,
                     "{}{} {}{} per turn",
-                    target,
+                    self.target.prefix(),
                     &resource,
                     sign,
                     amount.abs()
@@ -139,14 +368,25 @@ impl fmt::Display for CardEffect {
             }
             EffectType::Damage(amount, ignore_wall) => {
                 let ignore_wall = if ignore_wall { " (ignores shield)" } else { "" };
-                let target = if self.affects_user { "Takes" } else { "" };
-                return write!(f, "{} {} damage{}", target, amount, ignore_wall);
+                return write!(
+                    f,
+                    "{} {} damage{}",
+                    self.damage_prefix(),
+                    amount,
+                    ignore_wall
+                );
             }
             EffectType::ResourceChange(resource, amount) => {
                 let sign = if amount > 0 { "+" } else { "-" };
-                let target = if self.affects_user { "" } else { "Enemy " };
 
-                return write!(f, "{}{} {}{}", target, &resource, sign, amount.abs());
+                return write!(
+                    f,
+                    "{}{} {}{}",
+                    self.target.prefix(),
+                    &resource,
+                    sign,
+                    amount.abs()
+                );
             }
             EffectType::TowerGrowth(growth) => {
                 let sign = if growth > 0 { "+" } else { "-" };
@@ -156,13 +396,71 @@ impl fmt::Display for CardEffect {
                 let sign = if growth > 0 { "+" } else { "-" };
                 format!("{}{} Shield", sign, growth)
             }
+            EffectType::ScaledDamage(value, ignore_wall) => {
+                let ignore_wall = if ignore_wall { " (ignores shield)" } else { "" };
+                return write!(
+                    f,
+                    "{} damage = {}{}",
+                    self.damage_prefix(),
+                    value,
+                    ignore_wall
+                );
+            }
+            EffectType::SwapWalls => {
+                return write!(f, "Swap Shields");
+            }
+            EffectType::Steal(resource, amount) => {
+                return match self.target {
+                    Target::User => write!(f, "Enemy steals {} {}", amount, &resource),
+                    Target::Enemy => write!(f, "Steal {} {}", amount, &resource),
+                    Target::Both => write!(f, "Both players steal {} {}", amount, &resource),
+                };
+            }
+            EffectType::PlayAgain => {
+                return write!(f, "Play again");
+            }
+            EffectType::DiscardRandom(amount) => {
+                return write!(
+                    f,
+                    "{}Discards {} random card(s)",
+                    self.target.prefix(),
+                    amount
+                );
+            }
             EffectType::None => {
                 return write!(f, "");
             }
         };
-        if !self.affects_user {
-            result.push_str(" to enemy");
+        match self.target {
+            Target::User => {}
+            Target::Enemy => result.push_str(" to enemy"),
+            Target::Both => result.push_str(" to both players"),
         }
         write!(f, "{}", result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_is_read_from_affects_user_flag() {
+        let effects: Vec<CardEffect> = serde_json::from_str(
+            r#"[
+                {"affects_user": true, "effect_type": "PlayAgain"},
+                {"affects_user": false, "effect_type": {"TowerGrowth": 2}},
+                {"target": "Both", "effect_type": {"Damage": [1, false]}}
+            ]"#,
+        )
+        .unwrap();
+        let targets: Vec<Target> = effects.iter().map(|e| e.target).collect();
+        assert_eq!(targets, [Target::User, Target::Enemy, Target::Both]);
+
+        let json = serde_json::to_string(&effects[2]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CardEffect>(&json).unwrap(),
+            effects[2]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(feature = "bevy")]
+use bevy::{prelude::Component, reflect::Reflect};
+use rand::{Rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+
+use crate::data::card::Card;
+
+/// Cards the player draws from.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect))]
+pub struct Deck(pub Vec<Card>);
+
+impl Deref for Deck {
+    type Target = Vec<Card>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Card>> for Deck {
+    fn from(cards: Vec<Card>) -> Self {
+        Self(cards)
+    }
+}
+
+impl Deck {
+    /// Every card repeated by the amount of its copies.
+    pub fn all_copies(&self) -> Vec<Card> {
+        self.iter()
+            .flat_map(|card| std::iter::repeat_n(card, card.copies as usize))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Card referenced by its id, the way decks built by players are stored and sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub struct DeckEntry {
+    pub id: i32,
+    pub copies: u32,
+}
+
+/// Draw pile is shuffled, cards are drawn from its end.
+/// When it runs out the discard pile is shuffled into it.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect))]
+pub struct Piles {
+    pub draw: Vec<Card>,
+    pub discard: Vec<Card>,
+}
+
+impl Piles {
+    pub fn new(deck: &Deck, rng: &mut impl Rng) -> Self {
+        Self::shuffled(deck.all_copies(), rng)
+    }
+
+    pub fn shuffled(mut cards: Vec<Card>, rng: &mut impl Rng) -> Self {
+        cards.shuffle(rng);
+        Self {
+            draw: cards,
+            discard: Vec::new(),
+        }
+    }
+
+    /// Returns `None` only if both piles are empty.
+    pub fn draw(&mut self, rng: &mut impl Rng) -> Option<Card> {
+        if self.draw.is_empty() {
+            std::mem::swap(&mut self.draw, &mut self.discard);
+            self.draw.shuffle(rng);
+        }
+        self.draw.pop()
+    }
+
+    pub fn discard(&mut self, card: Card) {
+        self.discard.push(card);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::{self, RngStream};
+
+    fn card(id: i32, copies: u32) -> Card {
+        Card {
+            id,
+            copies,
+            ..Default::default()
+        }
+    }
+
+    fn count(cards: &[Card], id: i32) -> usize {
+        cards.iter().filter(|c| c.id == id).count()
+    }
+
+    #[test]
+    fn all_copies_repeats_cards() {
+        let deck = Deck(vec![card(1, 1), card(2, 3), card(3, 0)]);
+        let cards = deck.all_copies();
+
+        assert_eq!(cards.len(), 4);
+        assert_eq!(count(&cards, 1), 1);
+        assert_eq!(count(&cards, 2), 3);
+        assert_eq!(count(&cards, 3), 0);
+    }
+
+    #[test]
+    fn discard_pile_is_reshuffled_into_draw_pile() {
+        let mut rng = rng::seeded(1, RngStream::Match);
+        let deck = Deck(vec![card(1, 2), card(2, 1)]);
+        let mut piles = Piles::new(&deck, &mut rng);
+        assert_eq!(piles.draw.len(), 3);
+
+        let drawn: Vec<Card> = (0..3).filter_map(|_| piles.draw(&mut rng)).collect();
+        assert_eq!(count(&drawn, 1), 2);
+        assert_eq!(count(&drawn, 2), 1);
+        assert!(piles.draw.is_empty());
+
+        for card in drawn {
+            piles.discard(card);
+        }
+        assert!(piles.draw(&mut rng).is_some());
+        assert!(piles.discard.is_empty());
+        assert_eq!(piles.draw.len(), 2);
+    }
+
+    #[test]
+    fn drawing_from_empty_piles_returns_none() {
+        let mut rng = rng::seeded(1, RngStream::Match);
+        let mut piles = Piles::new(&Deck(vec![card(1, 1)]), &mut rng);
+
+        assert!(piles.draw(&mut rng).is_some());
+        assert_eq!(piles.draw(&mut rng), None);
+    }
+}
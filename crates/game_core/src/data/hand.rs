@@ -0,0 +1,32 @@
+#[cfg(feature = "bevy")]
+use bevy::{prelude::Component, reflect::Reflect};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+use crate::data::card::Card;
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect))]
+pub struct HandCards(pub Vec<Card>);
+
+impl Deref for HandCards {
+    type Target = Vec<Card>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HandCards {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl HandCards {
+    pub fn rnd(&self, rng: &mut impl Rng) -> usize {
+        let i: usize = rng.r#gen::<usize>() % self.len();
+        i
+    }
+}
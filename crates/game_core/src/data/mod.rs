@@ -1,4 +1,7 @@
 pub mod card;
+pub mod deck;
+pub mod hand;
 pub mod player;
 pub mod resource;
+pub mod ruleset;
 pub mod supply;
@@ -1,9 +1,35 @@
-use crate::consts;
+use crate::data::ruleset::Ruleset;
 #[cfg(feature = "bevy")]
 use bevy::{prelude::Component, reflect::Reflect};
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Debug, Clone, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Copy, Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect))]
+pub enum PlayerNumber {
+    #[default]
+    First,
+    Second,
+}
+
+impl PlayerNumber {
+    pub const ALL: [Self; 2] = [Self::First, Self::Second];
+
+    pub fn other(self) -> Self {
+        match self {
+            PlayerNumber::First => PlayerNumber::Second,
+            PlayerNumber::Second => PlayerNumber::First,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            PlayerNumber::First => 0,
+            PlayerNumber::Second => 1,
+        }
+    }
+}
+
+#[derive(Copy, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "bevy", derive(Component, Reflect))]
 pub struct PlayerHealth {
     pub tower_hp: i32,
@@ -12,28 +38,32 @@ pub struct PlayerHealth {
 
 impl Default for PlayerHealth {
     fn default() -> Self {
-        Self {
-            tower_hp: consts::BASE_TOWER_HP,
-            walls_hp: consts::BASE_WALLS_HP,
-        }
+        Self::new(&Ruleset::default())
     }
 }
 
 impl PlayerHealth {
+    pub fn new(rules: &Ruleset) -> Self {
+        Self {
+            tower_hp: rules.base_tower_hp,
+            walls_hp: rules.base_walls_hp,
+        }
+    }
+
     pub fn is_alive(&self) -> bool {
         self.tower_hp > 0
     }
 
-    pub fn has_max_possible_tower(&self) -> bool {
-        self.tower_hp >= consts::MAX_TOWER_HP
+    pub fn has_max_possible_tower(&self, rules: &Ruleset) -> bool {
+        self.tower_hp >= rules.max_tower_hp
     }
 
-    pub fn make_tower_higher(&mut self, amount: i32) {
-        self.tower_hp = (self.tower_hp + amount).min(consts::MAX_TOWER_HP);
+    pub fn make_tower_higher(&mut self, amount: i32, rules: &Ruleset) {
+        self.tower_hp = (self.tower_hp + amount).min(rules.max_tower_hp);
     }
 
-    pub fn make_walls_higher(&mut self, amount: i32) {
-        self.walls_hp = (self.walls_hp + amount).min(consts::MAX_WALLS_HP);
+    pub fn make_walls_higher(&mut self, amount: i32, rules: &Ruleset) {
+        self.walls_hp = (self.walls_hp + amount).min(rules.max_walls_hp);
     }
 
     pub fn give_damage(&mut self, amount: i32, ignore_wall: bool) {
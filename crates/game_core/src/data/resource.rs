@@ -1,4 +1,4 @@
-use crate::consts;
+use crate::data::ruleset::Ruleset;
 #[cfg(feature = "bevy")]
 use bevy::reflect::Reflect;
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,10 @@ pub enum ResourceType {
     Soldiers,
 }
 
+impl ResourceType {
+    pub const ALL: [Self; 3] = [Self::Tools, Self::Magic, Self::Soldiers];
+}
+
 impl fmt::Display for ResourceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -36,21 +40,21 @@ pub struct CastleResource {
 
 impl Default for CastleResource {
     fn default() -> Self {
-        CastleResource::new()
+        CastleResource::new(&Ruleset::default())
     }
 }
 
 impl CastleResource {
-    pub fn new() -> CastleResource {
+    pub fn new(rules: &Ruleset) -> CastleResource {
         CastleResource {
-            amount: consts::BASE_RESOURCE_AMOUNT,
-            production: consts::BASE_RESOURCE_PRODUCTION,
+            amount: rules.base_resource_amount,
+            production: rules.base_resource_production,
         }
     }
 
-    pub fn reset(&mut self) {
-        self.amount = consts::BASE_RESOURCE_AMOUNT;
-        self.production = consts::BASE_RESOURCE_PRODUCTION;
+    pub fn reset(&mut self, rules: &Ruleset) {
+        self.amount = rules.base_resource_amount;
+        self.production = rules.base_resource_production;
     }
 
     pub fn produce(&mut self) {
@@ -64,12 +68,12 @@ impl CastleResource {
         }
     }
 
-    pub fn change_production(&mut self, diff: i32) {
+    pub fn change_production(&mut self, diff: i32, rules: &Ruleset) {
         self.production += diff;
         if self.production <= 0 {
             self.production = 1;
-        } else if self.production > consts::MAX_PRODUCTION {
-            self.production = consts::MAX_PRODUCTION;
+        } else if self.production > rules.max_production {
+            self.production = rules.max_production;
         }
     }
 }
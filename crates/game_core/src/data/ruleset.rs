@@ -0,0 +1,91 @@
+#[cfg(feature = "bevy")]
+use bevy::{asset::Asset, prelude::Resource, reflect::Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::env::MAX_HAND;
+
+/// Numbers that define a match variant, missing fields use the Classic values.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Resource, Reflect))]
+#[serde(default)]
+pub struct Ruleset {
+    pub name: String,
+    pub hand_size: usize,
+    pub base_tower_hp: i32,
+    pub base_walls_hp: i32,
+    /// Tower height that wins the match
+    pub max_tower_hp: i32,
+    pub max_walls_hp: i32,
+    pub base_resource_amount: i32,
+    pub base_resource_production: i32,
+    pub max_production: i32,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self {
+            name: "Classic".to_owned(),
+            hand_size: 5,
+            base_tower_hp: 15,
+            base_walls_hp: 5,
+            max_tower_hp: 50,
+            max_walls_hp: 30,
+            base_resource_amount: 3,
+            base_resource_production: 1,
+            max_production: 10,
+        }
+    }
+}
+
+/// All rulesets available to pick, loaded from `all.rules.json`.
+/// Hands bigger than [`MAX_HAND`] can't be observed by the AI, so such rulesets are rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(Asset, Reflect))]
+#[serde(try_from = "Vec<Ruleset>")]
+pub struct RulesetsAsset(pub Vec<Ruleset>);
+
+impl TryFrom<Vec<Ruleset>> for RulesetsAsset {
+    type Error = String;
+
+    fn try_from(rulesets: Vec<Ruleset>) -> Result<Self, Self::Error> {
+        match rulesets.iter().find(|r| r.hand_size > MAX_HAND) {
+            Some(rules) => Err(format!(
+                "{} rules deal {} cards, at most {} are supported",
+                rules.name, rules.hand_size, MAX_HAND
+            )),
+            None => Ok(Self(rulesets)),
+        }
+    }
+}
+
+impl RulesetsAsset {
+    /// Ruleset after the one with the given name, wraps around.
+    pub fn next(&self, name: &str) -> Option<&Ruleset> {
+        let i = self.0.iter().position(|r| r.name == name);
+        let next = i.map_or(0, |i| (i + 1) % self.0.len());
+        self.0.get(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_rulesets_are_valid() {
+        let rulesets: RulesetsAsset =
+            serde_json::from_str(include_str!("../../../../assets/all.rules.json")).unwrap();
+        assert_eq!(rulesets.0[0], Ruleset::default());
+        assert!(rulesets.0.iter().all(|r| r.hand_size <= MAX_HAND));
+    }
+
+    #[test]
+    fn hand_bigger_than_observation_is_rejected() {
+        let json = format!(r#"[{{"name": "Big", "hand_size": {}}}]"#, MAX_HAND + 1);
+        let err = serde_json::from_str::<RulesetsAsset>(&json).unwrap_err();
+        assert!(err.to_string().contains("Big rules deal 9 cards"));
+
+        let json = format!(r#"[{{"name": "Big", "hand_size": {}}}]"#, MAX_HAND);
+        assert!(serde_json::from_str::<RulesetsAsset>(&json).is_ok());
+    }
+}
@@ -5,9 +5,10 @@ use serde::{Deserialize, Serialize};
 use crate::data::{
     card::Card,
     resource::{CastleResource, ResourceType},
+    ruleset::Ruleset,
 };
 
-#[derive(Default, Serialize, Deserialize, Debug)]
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "bevy", derive(Component, Reflect))]
 pub struct PlayerSupply {
     pub tools: CastleResource,
@@ -16,6 +17,14 @@ pub struct PlayerSupply {
 }
 
 impl PlayerSupply {
+    pub fn new(rules: &Ruleset) -> Self {
+        Self {
+            tools: CastleResource::new(rules),
+            magic: CastleResource::new(rules),
+            soldiers: CastleResource::new(rules),
+        }
+    }
+
     /// Call the produce method on each resource type
     pub fn update_resources(&mut self) {
         self.tools.produce();
@@ -30,11 +39,16 @@ impl PlayerSupply {
         };
     }
 
-    pub fn change_resource_production(&mut self, res_type: ResourceType, amount: i32) {
+    pub fn change_resource_production(
+        &mut self,
+        res_type: ResourceType,
+        amount: i32,
+        rules: &Ruleset,
+    ) {
         match res_type {
-            ResourceType::Magic => self.magic.change_production(amount),
-            ResourceType::Tools => self.tools.change_production(amount),
-            ResourceType::Soldiers => self.soldiers.change_production(amount),
+            ResourceType::Magic => self.magic.change_production(amount, rules),
+            ResourceType::Tools => self.tools.change_production(amount, rules),
+            ResourceType::Soldiers => self.soldiers.change_production(amount, rules),
         };
     }
 
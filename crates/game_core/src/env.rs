@@ -0,0 +1,321 @@
+//! Gym-style environment for training AI policies offline.
+//!
+//! The agent plays one seat of a match against an [`AiPolicy`] opponent, observations
+//! are fixed-size arrays of numbers and actions are indexes: `0..MAX_HAND` uses the card
+//! at that hand slot, `MAX_HAND..ACTION_COUNT` discards the card at slot `index - MAX_HAND`.
+use crate::ai::AiPolicy;
+use crate::data::{
+    deck::Deck, player::PlayerHealth, player::PlayerNumber, resource::ResourceType,
+    ruleset::Ruleset, supply::PlayerSupply,
+};
+use crate::r#match::{ActionTaken, MatchState, MatchView, RuleError};
+use crate::rng::{self, GameRng, RngStream};
+
+/// Hand slots in the observation, bigger than the hand of any ruleset.
+pub const MAX_HAND: usize = 8;
+/// Tower and walls, then amount and production of every resource, for both players.
+const PLAYER_SIZE: usize = 2 + ResourceType::ALL.len() * 2;
+pub const OBSERVATION_SIZE: usize = PLAYER_SIZE * 2 + MAX_HAND;
+pub const ACTION_COUNT: usize = MAX_HAND * 2;
+/// Value of the hand slots without a card
+pub const EMPTY_SLOT: f32 = -1.0;
+
+pub type Observation = [f32; OBSERVATION_SIZE];
+/// `true` for every action index the player can take
+pub type ActionMask = [bool; ACTION_COUNT];
+
+/// Player stats followed by the enemy ones and the ids of the hand cards.
+pub fn observe(view: &MatchView) -> Observation {
+    let mut obs = [EMPTY_SLOT; OBSERVATION_SIZE];
+    encode_player(&mut obs[..PLAYER_SIZE], view.health, view.supply);
+    encode_player(
+        &mut obs[PLAYER_SIZE..PLAYER_SIZE * 2],
+        view.enemy_health,
+        view.enemy_supply,
+    );
+    for (slot, card) in obs[PLAYER_SIZE * 2..].iter_mut().zip(view.hand.iter()) {
+        *slot = card.id as f32;
+    }
+    obs
+}
+
+fn encode_player(out: &mut [f32], health: &PlayerHealth, supply: &PlayerSupply) {
+    out[0] = health.tower_hp as f32;
+    out[1] = health.walls_hp as f32;
+    for (i, res) in ResourceType::ALL.into_iter().enumerate() {
+        out[2 + i * 2] = supply.get(res).amount as f32;
+        out[3 + i * 2] = supply.get(res).production as f32;
+    }
+}
+
+pub fn legal_mask(view: &MatchView) -> ActionMask {
+    let mut mask = [false; ACTION_COUNT];
+    for (i, card) in view.hand.iter().take(MAX_HAND).enumerate() {
+        mask[i] = view.supply.can_afford_card(card);
+        mask[MAX_HAND + i] = true;
+    }
+    mask
+}
+
+/// Turns the action index into the action, `None` when it can't be taken.
+pub fn to_action(view: &MatchView, index: usize) -> Option<ActionTaken> {
+    if !*legal_mask(view).get(index)? {
+        return None;
+    }
+    let card = view.hand[index % MAX_HAND].clone();
+    Some(if index < MAX_HAND {
+        ActionTaken::UseCard { card }
+    } else {
+        ActionTaken::DropCard { card }
+    })
+}
+
+/// Index of the action, the reverse of [`to_action`].
+pub fn action_index(view: &MatchView, action: &ActionTaken) -> Option<usize> {
+    let slot = view.hand.iter().position(|c| c == action.card())?;
+    match action {
+        ActionTaken::UseCard { .. } => Some(slot),
+        ActionTaken::DropCard { .. } => Some(MAX_HAND + slot),
+    }
+}
+
+pub struct Step {
+    pub observation: Observation,
+    /// 1 for winning the match, -1 for losing it, 0 otherwise
+    pub reward: f32,
+    /// The match ended or reached the turn limit
+    pub done: bool,
+}
+
+pub struct Env {
+    decks: [Deck; 2],
+    rules: Ruleset,
+    opponent: Box<dyn AiPolicy>,
+    /// Seat of the agent
+    pub me: PlayerNumber,
+    /// Turn after which the episode ends without a winner
+    pub max_turns: u32,
+    state: MatchState,
+    rng: GameRng,
+}
+
+impl Env {
+    /// Panics if the hand of the ruleset doesn't fit into the observation.
+    pub fn new(deck: Deck, rules: Ruleset, opponent: Box<dyn AiPolicy>) -> Self {
+        assert!(
+            rules.hand_size <= MAX_HAND,
+            "{} rules deal {} cards, the observation has only {} slots",
+            rules.name,
+            rules.hand_size,
+            MAX_HAND
+        );
+        let decks = [deck.clone(), deck];
+        Self {
+            state: MatchState::new(decks.clone(), 0, rules.clone()),
+            decks,
+            rules,
+            opponent,
+            me: PlayerNumber::First,
+            max_turns: 1000,
+            rng: rng::seeded(0, RngStream::Ai),
+        }
+    }
+
+    /// Starts a new match, the opponent moves first when the agent is in the second seat.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.state = MatchState::new(self.decks.clone(), seed, self.rules.clone());
+        self.rng = rng::seeded(seed, RngStream::Ai);
+        self.play_opponent();
+        self.observation()
+    }
+
+    /// Performs the agent action and the opponent moves until it is the agent turn again.
+    pub fn step(&mut self, action: usize) -> Result<Step, RuleError> {
+        if self.is_done() {
+            return Err(RuleError::MatchFinished);
+        }
+        let action =
+            to_action(&self.state.view(self.me), action).ok_or(RuleError::CardNotInHand)?;
+        self.state.apply_as(self.me, &action)?;
+        self.play_opponent();
+        let reward = match self.state.outcome() {
+            Some(outcome) if outcome.winner == self.me => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+        Ok(Step {
+            observation: self.observation(),
+            reward,
+            done: self.is_done(),
+        })
+    }
+
+    fn play_opponent(&mut self) {
+        while !self.is_done() && self.state.current != self.me {
+            let view = self.state.view(self.state.current);
+            let Some(action) = self.opponent.select_action(&view, &mut self.rng) else {
+                // without cards there is nothing to play, the opponent gives up
+                let _ = self.state.concede(self.state.current);
+                break;
+            };
+            self.state
+                .apply(&action)
+                .expect("Opponent picked an illegal action");
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.is_finished() || self.state.turn >= self.max_turns
+    }
+
+    pub fn observation(&self) -> Observation {
+        observe(&self.state.view(self.me))
+    }
+
+    pub fn legal_mask(&self) -> ActionMask {
+        legal_mask(&self.state.view(self.me))
+    }
+
+    pub fn state(&self) -> &MatchState {
+        &self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Greedy, RandomLegal};
+    use crate::data::card::Card;
+    use crate::data::resource::ResourceType;
+
+    fn deck() -> Deck {
+        let cards: Vec<Card> =
+            serde_json::from_str(include_str!("../../../assets/all.deck.json")).unwrap();
+        cards.into()
+    }
+
+    fn new_env() -> Env {
+        Env::new(deck(), Ruleset::default(), Box::new(Greedy))
+    }
+
+    #[test]
+    fn observation_holds_both_players_and_hand() {
+        let mut env = new_env();
+        let obs = env.reset(1);
+        let view = env.state().view(env.me);
+
+        assert_eq!(obs[0], view.health.tower_hp as f32);
+        assert_eq!(obs[1], view.health.walls_hp as f32);
+        assert_eq!(obs[2], view.supply.get(ResourceType::ALL[0]).amount as f32);
+        assert_eq!(
+            obs[3],
+            view.supply.get(ResourceType::ALL[0]).production as f32
+        );
+        assert_eq!(obs[PLAYER_SIZE], view.enemy_health.tower_hp as f32);
+        let hand = &obs[PLAYER_SIZE * 2..];
+        for (slot, card) in hand.iter().zip(view.hand.iter()) {
+            assert_eq!(*slot, card.id as f32);
+        }
+        assert!(hand[view.hand.len()..].iter().all(|s| *s == EMPTY_SLOT));
+    }
+
+    #[test]
+    fn mask_allows_affordable_cards_and_every_discard() {
+        let mut env = new_env();
+        env.reset(1);
+        let view = env.state().view(env.me);
+        let mask = legal_mask(&view);
+
+        for (i, card) in view.hand.iter().enumerate() {
+            assert_eq!(mask[i], view.supply.can_afford_card(card));
+            assert!(mask[MAX_HAND + i]);
+        }
+        for i in view.hand.len()..MAX_HAND {
+            assert!(!mask[i]);
+            assert!(!mask[MAX_HAND + i]);
+        }
+    }
+
+    #[test]
+    fn action_index_is_reverse_of_to_action() {
+        let mut env = new_env();
+        env.reset(1);
+        let view = env.state().view(env.me);
+        let mask = legal_mask(&view);
+
+        for (index, legal) in mask.into_iter().enumerate() {
+            match to_action(&view, index) {
+                Some(action) => {
+                    assert!(legal);
+                    assert_eq!(action_index(&view, &action), Some(index));
+                }
+                None => assert!(!legal),
+            }
+        }
+        assert_eq!(to_action(&view, ACTION_COUNT), None);
+    }
+
+    #[test]
+    fn reset_with_same_seed_repeats_match() {
+        let mut env = new_env();
+        env.me = PlayerNumber::Second;
+        let obs = env.reset(7);
+        // the opponent already moved, so it is the agent turn
+        assert_eq!(env.state().current, PlayerNumber::Second);
+        assert_eq!(env.state().turn, 1);
+
+        let mut other = new_env();
+        other.me = PlayerNumber::Second;
+        assert_eq!(other.reset(7), obs);
+    }
+
+    #[test]
+    fn step_plays_until_agent_turn_and_rewards_outcome() {
+        let mut env = new_env();
+        env.reset(3);
+        let mut rng = rng::seeded(3, RngStream::Ai);
+        let mut step = None;
+        while !env.is_done() {
+            let view = env.state().view(env.me);
+            let action = RandomLegal.select_action(&view, &mut rng).unwrap();
+            let index = action_index(&view, &action).unwrap();
+            let result = env.step(index).unwrap();
+            assert_eq!(result.observation, env.observation());
+            assert_eq!(result.done, env.is_done());
+            if !result.done {
+                assert_eq!(env.state().current, env.me);
+                assert_eq!(result.reward, 0.0);
+            }
+            step = Some(result);
+        }
+
+        let outcome = env
+            .state()
+            .outcome()
+            .expect("Match ended before the turn limit");
+        let reward = if outcome.winner == env.me { 1.0 } else { -1.0 };
+        assert_eq!(step.unwrap().reward, reward);
+        assert_eq!(env.step(0).err(), Some(RuleError::MatchFinished));
+    }
+
+    #[test]
+    fn illegal_action_is_rejected() {
+        let mut env = new_env();
+        env.reset(1);
+        let hand_size = env.state().player(env.me).hand.len();
+        let before = env.observation();
+
+        assert_eq!(env.step(hand_size).err(), Some(RuleError::CardNotInHand));
+        assert_eq!(env.observation(), before);
+    }
+
+    #[test]
+    #[should_panic(expected = "observation has only 8 slots")]
+    fn hand_bigger_than_observation_panics() {
+        let rules = Ruleset {
+            hand_size: MAX_HAND + 1,
+            ..Ruleset::default()
+        };
+        Env::new(deck(), rules, Box::new(Greedy));
+    }
+}
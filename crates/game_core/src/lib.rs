@@ -1,8 +1,14 @@
 #[cfg(feature = "bevy")]
 use bevy::prelude::*;
 
+pub mod ai;
 pub mod consts;
 pub mod data;
+pub mod env;
+pub mod r#match;
+pub mod protocol;
+pub mod rng;
+pub mod validate;
 
 #[cfg(feature = "bevy")]
 pub struct GameCorePlugin;
@@ -12,6 +18,10 @@ impl Plugin for GameCorePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<crate::data::supply::PlayerSupply>()
             .register_type::<crate::data::player::PlayerHealth>()
-            .register_type::<crate::data::card::Card>();
+            .register_type::<crate::data::card::Card>()
+            .register_type::<crate::data::hand::HandCards>()
+            .register_type::<crate::data::deck::Deck>()
+            .register_type::<crate::data::ruleset::Ruleset>()
+            .register_type::<crate::data::player::PlayerNumber>();
     }
 }
@@ -0,0 +1,1042 @@
+//! Engine-free match rules.
+//!
+//! [`MatchState`] holds everything needed to play a game between two players
+//! and [`MatchState::apply`] is the only way to advance it, so the same rules
+//! can be driven by the Bevy game, tests or bulk simulations.
+#[cfg(feature = "bevy")]
+use bevy::prelude::{Component, Resource};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::data::{
+    card::{Card, CardEffect, EffectType, Stat, Value},
+    deck::{Deck, Piles},
+    hand::HandCards,
+    player::{PlayerHealth, PlayerNumber},
+    resource::ResourceType,
+    ruleset::Ruleset,
+    supply::PlayerSupply,
+};
+use crate::rng::{self, GameRng, RngStream};
+
+/// Action performed by the player that currently has the turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum ActionTaken {
+    UseCard { card: Card },
+    DropCard { card: Card },
+}
+
+impl ActionTaken {
+    pub fn card(&self) -> &Card {
+        match self {
+            ActionTaken::UseCard { card } | ActionTaken::DropCard { card } => card,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MatchEvent {
+    CardUsed {
+        player: PlayerNumber,
+        card: Card,
+    },
+    CardDropped {
+        player: PlayerNumber,
+        card: Card,
+    },
+    CardDrawn {
+        player: PlayerNumber,
+        card: Card,
+    },
+    /// Card removed from the hand by an effect of the other card
+    CardDiscarded {
+        player: PlayerNumber,
+        card: Card,
+    },
+    TurnStarted {
+        player: PlayerNumber,
+        turn: u32,
+    },
+    MatchEnded(MatchOutcome),
+}
+
+pub type Events = Vec<MatchEvent>;
+
+/// Reason for rejecting an action, the match state is never changed by a rejected action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleError {
+    MatchFinished,
+    NotPlayerTurn(PlayerNumber),
+    CardNotInHand,
+    CannotAfford {
+        resource: ResourceType,
+        cost: i32,
+        available: i32,
+    },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::MatchFinished => write!(f, "match has already finished"),
+            RuleError::NotPlayerTurn(nr) => write!(f, "it is not {:?} player turn", nr),
+            RuleError::CardNotInHand => write!(f, "card is not in the player's hand"),
+            RuleError::CannotAfford {
+                resource,
+                cost,
+                available,
+            } => write!(
+                f,
+                "card costs {} {} but only {} is available",
+                cost, resource, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WinReason {
+    /// Opponent tower got destroyed
+    TowerDestroyed,
+    /// Winner tower reached the max height
+    TowerCompleted,
+    /// Opponent gave up the match
+    Conceded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MatchOutcome {
+    pub winner: PlayerNumber,
+    pub reason: WinReason,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub health: PlayerHealth,
+    pub supply: PlayerSupply,
+    pub hand: HandCards,
+    pub deck: Deck,
+    pub piles: Piles,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct MatchState {
+    pub players: [PlayerState; 2],
+    pub current: PlayerNumber,
+    pub turn: u32,
+    pub seed: u64,
+    pub rules: Ruleset,
+    /// Player that gave up the match
+    #[serde(default)]
+    pub conceded: Option<PlayerNumber>,
+    rng: GameRng,
+}
+
+impl MatchState {
+    /// Creates a new match with hands dealt from the decks of the players.
+    /// The first player gets the production for the first turn right away.
+    pub fn new(decks: [Deck; 2], seed: u64, rules: Ruleset) -> Self {
+        let mut rng = rng::seeded(seed, RngStream::Match);
+        let players = decks.map(|deck| {
+            let mut piles = Piles::new(&deck, &mut rng);
+            let hand = (0..rules.hand_size)
+                .filter_map(|_| piles.draw(&mut rng))
+                .collect();
+            PlayerState {
+                health: PlayerHealth::new(&rules),
+                supply: PlayerSupply::new(&rules),
+                hand: HandCards(hand),
+                deck,
+                piles,
+            }
+        });
+        let mut state = Self {
+            players,
+            current: PlayerNumber::First,
+            turn: 0,
+            seed,
+            rules,
+            conceded: None,
+            rng,
+        };
+        state.current_player_mut().supply.update_resources();
+        state
+    }
+
+    pub fn player(&self, nr: PlayerNumber) -> &PlayerState {
+        &self.players[nr.index()]
+    }
+
+    pub fn player_mut(&mut self, nr: PlayerNumber) -> &mut PlayerState {
+        &mut self.players[nr.index()]
+    }
+
+    pub fn current_player(&self) -> &PlayerState {
+        self.player(self.current)
+    }
+
+    pub fn current_player_mut(&mut self) -> &mut PlayerState {
+        self.player_mut(self.current)
+    }
+
+    /// Part of the match visible to the given player.
+    pub fn view(&self, nr: PlayerNumber) -> MatchView<'_> {
+        let me = self.player(nr);
+        let enemy = self.player(nr.other());
+        MatchView {
+            me: nr,
+            current: self.current,
+            turn: self.turn,
+            health: &me.health,
+            supply: &me.supply,
+            hand: &me.hand,
+            enemy_health: &enemy.health,
+            enemy_supply: &enemy.supply,
+            deck: &me.deck,
+            enemy_deck: &enemy.deck,
+            discard: &me.piles.discard,
+            enemy_discard: &enemy.piles.discard,
+            enemy_hand_size: enemy.hand.len(),
+            rules: &self.rules,
+        }
+    }
+
+    /// Actions the current player can take.
+    pub fn legal_actions(&self) -> Vec<ActionTaken> {
+        self.current_player().legal_actions()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    pub fn outcome(&self) -> Option<MatchOutcome> {
+        if let Some(loser) = self.conceded {
+            return Some(MatchOutcome {
+                winner: loser.other(),
+                reason: WinReason::Conceded,
+            });
+        }
+        if let Some(winner) = PlayerNumber::ALL
+            .into_iter()
+            .find(|nr| self.player(*nr).health.has_max_possible_tower(&self.rules))
+        {
+            return Some(MatchOutcome {
+                winner,
+                reason: WinReason::TowerCompleted,
+            });
+        }
+        PlayerNumber::ALL
+            .into_iter()
+            .find(|nr| !self.player(*nr).health.is_alive())
+            .map(|loser| MatchOutcome {
+                winner: loser.other(),
+                reason: WinReason::TowerDestroyed,
+            })
+    }
+
+    /// Checks if the current player can perform the action,
+    /// returns the index of the card in the hand.
+    pub fn validate(&self, action: &ActionTaken) -> Result<usize, RuleError> {
+        if self.is_finished() {
+            return Err(RuleError::MatchFinished);
+        }
+        let player = self.current_player();
+        let index = player
+            .hand
+            .iter()
+            .position(|c| c == action.card())
+            .ok_or(RuleError::CardNotInHand)?;
+        match action {
+            ActionTaken::UseCard { card } if !player.supply.can_afford_card(card) => {
+                Err(RuleError::CannotAfford {
+                    resource: card.cost_resource,
+                    cost: card.cost_amount,
+                    available: player.supply.get(card.cost_resource).amount,
+                })
+            }
+            _ => Ok(index),
+        }
+    }
+
+    /// Ends the match with the other player as the winner, `player` doesn't need to have the turn.
+    pub fn concede(&mut self, player: PlayerNumber) -> Result<Events, RuleError> {
+        if self.is_finished() {
+            return Err(RuleError::MatchFinished);
+        }
+        self.conceded = Some(player);
+        Ok(self
+            .outcome()
+            .map(MatchEvent::MatchEnded)
+            .into_iter()
+            .collect())
+    }
+
+    /// Same as [`MatchState::apply`] but first checks that it is the turn of `player`.
+    pub fn apply_as(
+        &mut self,
+        player: PlayerNumber,
+        action: &ActionTaken,
+    ) -> Result<Events, RuleError> {
+        if player != self.current {
+            return Err(RuleError::NotPlayerTurn(player));
+        }
+        self.apply(action)
+    }
+
+    /// Performs the action for the current player, draws a replacement card
+    /// and passes the turn to the other player.
+    pub fn apply(&mut self, action: &ActionTaken) -> Result<Events, RuleError> {
+        let index = self.validate(action)?;
+        let player = self.current;
+        let mut events = Events::new();
+        let mut play_again = false;
+
+        match action {
+            ActionTaken::UseCard { card } => {
+                self.current_player_mut()
+                    .supply
+                    .change_resource_amount(card.cost_resource, -card.cost_amount);
+                events.push(MatchEvent::CardUsed {
+                    player,
+                    card: card.clone(),
+                });
+                for effect in card.effects.iter() {
+                    play_again |= self.resolve_effect(player, effect, &mut events);
+                }
+            }
+            ActionTaken::DropCard { card } => events.push(MatchEvent::CardDropped {
+                player,
+                card: card.clone(),
+            }),
+        }
+
+        if let Some(card) = self.players[player.index()].replace_card(index, &mut self.rng) {
+            events.push(MatchEvent::CardDrawn { player, card });
+        }
+
+        if let Some(outcome) = self.outcome() {
+            events.push(MatchEvent::MatchEnded(outcome));
+            return Ok(events);
+        }
+
+        self.turn += 1;
+        if !play_again {
+            self.current = player.other();
+            self.current_player_mut().supply.update_resources();
+        }
+        events.push(MatchEvent::TurnStarted {
+            player: self.current,
+            turn: self.turn,
+        });
+        Ok(events)
+    }
+
+    /// Resolves a single effect of the card used by `user`,
+    /// returns true if the user should take another turn.
+    fn resolve_effect(
+        &mut self,
+        user: PlayerNumber,
+        effect: &CardEffect,
+        events: &mut Events,
+    ) -> bool {
+        if let Some(condition) = effect.condition {
+            let left = self.value(user, condition.left);
+            let right = self.value(user, condition.right);
+            if !condition.cmp.check(left, right) {
+                return false;
+            }
+        }
+        // the value is read once, so damaging both players doesn't change it in between
+        let effect_type = match effect.effect_type {
+            EffectType::ScaledDamage(value, ignore_wall) => {
+                EffectType::Damage(self.value(user, value).max(0), ignore_wall)
+            }
+            EffectType::SwapWalls => {
+                let [first, second] = &mut self.players;
+                std::mem::swap(&mut first.health.walls_hp, &mut second.health.walls_hp);
+                return false;
+            }
+            EffectType::PlayAgain => return true,
+            effect_type => effect_type,
+        };
+        for target in effect.target.players(user) {
+            self.affect_player(target, effect_type, events);
+        }
+        false
+    }
+
+    /// Resolves the effect that changes a single player.
+    fn affect_player(
+        &mut self,
+        target: PlayerNumber,
+        effect_type: EffectType,
+        events: &mut Events,
+    ) {
+        let rules = &self.rules;
+        match effect_type {
+            EffectType::ProductionChange(resource, amount) => self.players[target.index()]
+                .supply
+                .change_resource_production(resource, amount, rules),
+            EffectType::ResourceChange(resource, amount) => self
+                .player_mut(target)
+                .supply
+                .change_resource_amount(resource, amount),
+            EffectType::Damage(amount, ignore_wall) => self
+                .player_mut(target)
+                .health
+                .give_damage(amount, ignore_wall),
+            EffectType::TowerGrowth(amount) => self.players[target.index()]
+                .health
+                .make_tower_higher(amount, rules),
+            EffectType::WallsGrowth(amount) => self.players[target.index()]
+                .health
+                .make_walls_higher(amount, rules),
+            EffectType::Steal(resource, amount) => {
+                let available = self.player(target).supply.get(resource).amount;
+                let stolen = amount.min(available).max(0);
+                self.player_mut(target)
+                    .supply
+                    .change_resource_amount(resource, -stolen);
+                self.player_mut(target.other())
+                    .supply
+                    .change_resource_amount(resource, stolen);
+            }
+            EffectType::DiscardRandom(amount) => {
+                for _ in 0..amount {
+                    let state = &mut self.players[target.index()];
+                    if state.hand.is_empty() {
+                        break;
+                    }
+                    let index = state.hand.rnd(&mut self.rng);
+                    events.push(MatchEvent::CardDiscarded {
+                        player: target,
+                        card: state.hand[index].clone(),
+                    });
+                    if let Some(card) = state.replace_card(index, &mut self.rng) {
+                        events.push(MatchEvent::CardDrawn {
+                            player: target,
+                            card,
+                        });
+                    }
+                }
+            }
+            // resolved for both players at once by `resolve_effect`
+            EffectType::ScaledDamage(..)
+            | EffectType::SwapWalls
+            | EffectType::PlayAgain
+            | EffectType::None => {}
+        }
+    }
+
+    /// Resolves the value from the perspective of the card user.
+    pub fn value(&self, user: PlayerNumber, value: Value) -> i32 {
+        match value {
+            Value::Fixed(v) => v,
+            Value::Own(stat) => self.player(user).stat(stat),
+            Value::Enemy(stat) => self.player(user.other()).stat(stat),
+        }
+    }
+}
+
+impl PlayerState {
+    /// Moves the card from the hand to the discard pile and draws a new one in its place.
+    /// The hand gets smaller if there is nothing left to draw.
+    fn replace_card(&mut self, index: usize, rng: &mut impl Rng) -> Option<Card> {
+        let card = self.hand[index].clone();
+        self.piles.discard(card);
+        match self.piles.draw(rng) {
+            Some(card) => {
+                self.hand[index] = card.clone();
+                Some(card)
+            }
+            None => {
+                self.hand.remove(index);
+                None
+            }
+        }
+    }
+
+    pub fn stat(&self, stat: Stat) -> i32 {
+        match stat {
+            Stat::Tower => self.health.tower_hp,
+            Stat::Walls => self.health.walls_hp,
+            Stat::Amount(resource) => self.supply.get(resource).amount,
+            Stat::Production(resource) => self.supply.get(resource).production,
+        }
+    }
+
+    pub fn legal_actions(&self) -> Vec<ActionTaken> {
+        self.hand
+            .iter()
+            .filter(|c| self.supply.can_afford_card(c))
+            .map(|card| ActionTaken::UseCard { card: card.clone() })
+            .chain(
+                self.hand
+                    .iter()
+                    .map(|card| ActionTaken::DropCard { card: card.clone() }),
+            )
+            .collect()
+    }
+}
+
+/// Information about the match available to one of the players:
+/// everything except the enemy hand and upcoming card draws.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchView<'a> {
+    pub me: PlayerNumber,
+    pub current: PlayerNumber,
+    pub turn: u32,
+    pub health: &'a PlayerHealth,
+    pub supply: &'a PlayerSupply,
+    pub hand: &'a HandCards,
+    pub enemy_health: &'a PlayerHealth,
+    pub enemy_supply: &'a PlayerSupply,
+    /// Cards that can be drawn by the player
+    pub deck: &'a Deck,
+    /// Cards that can be drawn by the enemy
+    pub enemy_deck: &'a Deck,
+    pub discard: &'a [Card],
+    pub enemy_discard: &'a [Card],
+    pub enemy_hand_size: usize,
+    pub rules: &'a Ruleset,
+}
+
+impl MatchView<'_> {
+    pub fn legal_actions(&self) -> Vec<ActionTaken> {
+        PlayerState {
+            health: *self.health,
+            supply: self.supply.clone(),
+            hand: self.hand.clone(),
+            ..Default::default()
+        }
+        .legal_actions()
+    }
+
+    /// Builds a full match from the visible information,
+    /// the enemy hand and the order of both draw piles are sampled with `rng`.
+    pub fn determinize(&self, rng: &mut impl Rng) -> MatchState {
+        let seed = rng.r#gen();
+        let mut match_rng = rng::seeded(seed, RngStream::Match);
+
+        let mut unknown = self.deck.all_copies();
+        remove_cards(&mut unknown, self.hand.iter().chain(self.discard));
+        let me = PlayerState {
+            health: *self.health,
+            supply: self.supply.clone(),
+            hand: self.hand.clone(),
+            deck: self.deck.clone(),
+            piles: Piles {
+                discard: self.discard.to_vec(),
+                ..Piles::shuffled(unknown, &mut match_rng)
+            },
+        };
+
+        let mut unknown = self.enemy_deck.all_copies();
+        remove_cards(&mut unknown, self.enemy_discard.iter());
+        let mut piles = Piles::shuffled(unknown, &mut match_rng);
+        let hand_size = self.enemy_hand_size.min(piles.draw.len());
+        let hand = piles.draw.split_off(piles.draw.len() - hand_size);
+        piles.discard = self.enemy_discard.to_vec();
+        let enemy = PlayerState {
+            health: *self.enemy_health,
+            supply: self.enemy_supply.clone(),
+            hand: HandCards(hand),
+            deck: self.enemy_deck.clone(),
+            piles,
+        };
+        let players = match self.me {
+            PlayerNumber::First => [me, enemy],
+            PlayerNumber::Second => [enemy, me],
+        };
+        MatchState {
+            players,
+            current: self.current,
+            turn: self.turn,
+            seed,
+            rules: self.rules.clone(),
+            conceded: None,
+            rng: match_rng,
+        }
+    }
+}
+
+/// Removes one copy of every card in `removed` from `cards`.
+fn remove_cards<'a>(cards: &mut Vec<Card>, removed: impl Iterator<Item = &'a Card>) {
+    for card in removed {
+        if let Some(i) = cards.iter().position(|c| c == card) {
+            cards.swap_remove(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::card::{Comparison, Condition, Target};
+
+    fn card(id: i32, cost_amount: i32, effects: Vec<CardEffect>) -> Card {
+        Card {
+            name: format!("Card {}", id),
+            id,
+            cost_amount,
+            cost_resource: ResourceType::Tools,
+            effects,
+            ..Default::default()
+        }
+    }
+
+    fn effect(target: Target, effect_type: EffectType) -> CardEffect {
+        CardEffect {
+            target,
+            effect_type,
+            condition: None,
+        }
+    }
+
+    fn deck() -> Deck {
+        (1..=10)
+            .map(|id| {
+                card(
+                    id,
+                    1,
+                    vec![effect(Target::Enemy, EffectType::Damage(1, false))],
+                )
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn new_match(seed: u64) -> MatchState {
+        MatchState::new([deck(), deck()], seed, Ruleset::default())
+    }
+
+    /// Replaces the first card in the hand of the current player, so it can be played.
+    fn give(state: &mut MatchState, card: Card) -> ActionTaken {
+        state.current_player_mut().hand[0] = card.clone();
+        ActionTaken::UseCard { card }
+    }
+
+    fn tools(state: &MatchState, nr: PlayerNumber) -> i32 {
+        state.player(nr).supply.get(ResourceType::Tools).amount
+    }
+
+    #[test]
+    fn new_match_produces_for_first_player() {
+        let state = new_match(1);
+        let rules = Ruleset::default();
+        let produced = rules.base_resource_amount + rules.base_resource_production;
+        assert_eq!(state.current, PlayerNumber::First);
+        assert_eq!(tools(&state, PlayerNumber::First), produced);
+        assert_eq!(
+            tools(&state, PlayerNumber::Second),
+            rules.base_resource_amount
+        );
+        for nr in PlayerNumber::ALL {
+            assert_eq!(state.player(nr).hand.len(), rules.hand_size);
+        }
+    }
+
+    #[test]
+    fn dropping_card_passes_turn_and_produces() {
+        let mut state = new_match(1);
+        let card = state.current_player().hand[0].clone();
+        let events = state
+            .apply(&ActionTaken::DropCard { card: card.clone() })
+            .unwrap();
+
+        assert_eq!(state.current, PlayerNumber::Second);
+        assert_eq!(state.turn, 1);
+        assert_eq!(tools(&state, PlayerNumber::First), 4);
+        assert_eq!(tools(&state, PlayerNumber::Second), 4);
+        assert_eq!(state.player(PlayerNumber::First).hand.len(), 5);
+        assert_eq!(
+            state.player(PlayerNumber::First).piles.discard,
+            vec![card.clone()]
+        );
+        assert_eq!(
+            events.first(),
+            Some(&MatchEvent::CardDropped {
+                player: PlayerNumber::First,
+                card
+            })
+        );
+        assert_eq!(
+            events.last(),
+            Some(&MatchEvent::TurnStarted {
+                player: PlayerNumber::Second,
+                turn: 1
+            })
+        );
+    }
+
+    #[test]
+    fn using_card_pays_cost_and_resolves_effects() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(
+                20,
+                3,
+                vec![effect(Target::Enemy, EffectType::Damage(2, false))],
+            ),
+        );
+        state.apply(&action).unwrap();
+
+        assert_eq!(tools(&state, PlayerNumber::First), 1);
+        assert_eq!(state.player(PlayerNumber::Second).health.walls_hp, 3);
+        assert_eq!(state.current, PlayerNumber::Second);
+    }
+
+    #[test]
+    fn destroying_tower_wins() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(Target::Enemy, EffectType::Damage(100, true))],
+            ),
+        );
+        let events = state.apply(&action).unwrap();
+        let outcome = MatchOutcome {
+            winner: PlayerNumber::First,
+            reason: WinReason::TowerDestroyed,
+        };
+
+        assert_eq!(state.outcome(), Some(outcome));
+        assert_eq!(events.last(), Some(&MatchEvent::MatchEnded(outcome)));
+        // the turn is not passed once the match ends
+        assert_eq!(state.current, PlayerNumber::First);
+        assert_eq!(state.turn, 0);
+    }
+
+    #[test]
+    fn completing_tower_wins() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(Target::User, EffectType::TowerGrowth(100))],
+            ),
+        );
+        let events = state.apply(&action).unwrap();
+        let outcome = MatchOutcome {
+            winner: PlayerNumber::First,
+            reason: WinReason::TowerCompleted,
+        };
+
+        assert_eq!(
+            state.player(PlayerNumber::First).health.tower_hp,
+            state.rules.max_tower_hp
+        );
+        assert_eq!(state.outcome(), Some(outcome));
+        assert_eq!(events.last(), Some(&MatchEvent::MatchEnded(outcome)));
+    }
+
+    /// Plays the first affordable card, or drops the first one, until the match ends.
+    fn play_out(state: &mut MatchState) -> Events {
+        let mut events = Events::new();
+        for _ in 0..500 {
+            if state.is_finished() {
+                break;
+            }
+            let card = state.current_player().hand[0].clone();
+            let action = if state.current_player().supply.can_afford_card(&card) {
+                ActionTaken::UseCard { card }
+            } else {
+                ActionTaken::DropCard { card }
+            };
+            events.extend(state.apply(&action).unwrap());
+        }
+        events
+    }
+
+    #[test]
+    fn same_seed_plays_same_match() {
+        let mut first = new_match(42);
+        let mut second = new_match(42);
+        assert_eq!(first.players[0].hand, second.players[0].hand);
+        assert_eq!(first.players[1].piles, second.players[1].piles);
+
+        let events = play_out(&mut first);
+        assert_eq!(events, play_out(&mut second));
+        assert!(first.is_finished());
+        assert_eq!(first.outcome(), second.outcome());
+        assert_eq!(first.turn, second.turn);
+        for nr in PlayerNumber::ALL {
+            assert_eq!(first.player(nr).hand, second.player(nr).hand);
+            assert_eq!(first.player(nr).piles, second.player(nr).piles);
+        }
+    }
+
+    /// Match state has no `PartialEq`, its debug output is compared instead.
+    fn snapshot(state: &MatchState) -> String {
+        format!("{:?}", state)
+    }
+
+    #[test]
+    fn rejects_action_of_other_player() {
+        let mut state = new_match(1);
+        let card = state.player(PlayerNumber::Second).hand[0].clone();
+        let before = snapshot(&state);
+
+        assert_eq!(
+            state.apply_as(PlayerNumber::Second, &ActionTaken::DropCard { card }),
+            Err(RuleError::NotPlayerTurn(PlayerNumber::Second))
+        );
+        assert_eq!(snapshot(&state), before);
+    }
+
+    #[test]
+    fn rejects_card_not_in_hand() {
+        let mut state = new_match(1);
+        let action = ActionTaken::UseCard {
+            card: card(99, 0, vec![]),
+        };
+        let before = snapshot(&state);
+
+        assert_eq!(state.validate(&action), Err(RuleError::CardNotInHand));
+        assert_eq!(
+            state.apply_as(PlayerNumber::First, &action),
+            Err(RuleError::CardNotInHand)
+        );
+        assert_eq!(snapshot(&state), before);
+    }
+
+    #[test]
+    fn rejects_card_that_cannot_be_afforded() {
+        let mut state = new_match(1);
+        let action = give(&mut state, card(20, 10, vec![]));
+        let before = snapshot(&state);
+        let err = RuleError::CannotAfford {
+            resource: ResourceType::Tools,
+            cost: 10,
+            available: 4,
+        };
+
+        assert_eq!(state.validate(&action), Err(err));
+        assert_eq!(state.apply(&action), Err(err));
+        assert_eq!(snapshot(&state), before);
+        // the card can still be dropped
+        let ActionTaken::UseCard { card } = action else {
+            unreachable!()
+        };
+        assert!(state.apply(&ActionTaken::DropCard { card }).is_ok());
+    }
+
+    #[test]
+    fn rejects_actions_after_match_ended() {
+        let mut state = new_match(1);
+        state.concede(PlayerNumber::Second).unwrap();
+        let card = state.current_player().hand[0].clone();
+        let action = ActionTaken::DropCard { card };
+        let before = snapshot(&state);
+
+        assert_eq!(state.validate(&action), Err(RuleError::MatchFinished));
+        assert_eq!(
+            state.apply_as(PlayerNumber::First, &action),
+            Err(RuleError::MatchFinished)
+        );
+        assert_eq!(
+            state.concede(PlayerNumber::First),
+            Err(RuleError::MatchFinished)
+        );
+        assert_eq!(snapshot(&state), before);
+    }
+
+    #[test]
+    fn swap_walls_exchanges_walls() {
+        let mut state = new_match(1);
+        state.player_mut(PlayerNumber::First).health.walls_hp = 12;
+        state.player_mut(PlayerNumber::Second).health.walls_hp = 2;
+        let action = give(
+            &mut state,
+            card(20, 0, vec![effect(Target::Enemy, EffectType::SwapWalls)]),
+        );
+        state.apply(&action).unwrap();
+
+        assert_eq!(state.player(PlayerNumber::First).health.walls_hp, 2);
+        assert_eq!(state.player(PlayerNumber::Second).health.walls_hp, 12);
+    }
+
+    #[test]
+    fn steal_moves_resources_to_user() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(
+                    Target::Enemy,
+                    EffectType::Steal(ResourceType::Tools, 2),
+                )],
+            ),
+        );
+        state.apply(&action).unwrap();
+
+        // enemy had 3 tools, the production comes after the steal
+        assert_eq!(tools(&state, PlayerNumber::First), 6);
+        assert_eq!(tools(&state, PlayerNumber::Second), 1 + 1);
+    }
+
+    #[test]
+    fn steal_is_limited_to_available_amount() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(
+                    Target::Enemy,
+                    EffectType::Steal(ResourceType::Tools, 10),
+                )],
+            ),
+        );
+        state.apply(&action).unwrap();
+
+        assert_eq!(tools(&state, PlayerNumber::First), 4 + 3);
+        assert_eq!(tools(&state, PlayerNumber::Second), 1);
+    }
+
+    #[test]
+    fn play_again_keeps_turn_without_production() {
+        let mut state = new_match(1);
+        let action = give(
+            &mut state,
+            card(20, 1, vec![effect(Target::User, EffectType::PlayAgain)]),
+        );
+        let events = state.apply(&action).unwrap();
+
+        assert_eq!(state.current, PlayerNumber::First);
+        assert_eq!(state.turn, 1);
+        assert_eq!(tools(&state, PlayerNumber::First), 3);
+        assert_eq!(tools(&state, PlayerNumber::Second), 3);
+        assert_eq!(
+            events.last(),
+            Some(&MatchEvent::TurnStarted {
+                player: PlayerNumber::First,
+                turn: 1
+            })
+        );
+    }
+
+    #[test]
+    fn discard_random_replaces_enemy_cards() {
+        let mut state = new_match(1);
+        let enemy_hand = state.player(PlayerNumber::Second).hand.clone();
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(Target::Enemy, EffectType::DiscardRandom(2))],
+            ),
+        );
+        let events = state.apply(&action).unwrap();
+
+        let discarded: Vec<&Card> = events
+            .iter()
+            .filter_map(|e| match e {
+                MatchEvent::CardDiscarded { player, card } => {
+                    assert_eq!(*player, PlayerNumber::Second);
+                    Some(card)
+                }
+                _ => None,
+            })
+            .collect();
+        let enemy = state.player(PlayerNumber::Second);
+        assert_eq!(discarded.len(), 2);
+        assert!(discarded.iter().all(|card| enemy_hand.contains(card)));
+        assert_eq!(enemy.hand.len(), state.rules.hand_size);
+        assert_eq!(enemy.piles.discard.len(), 2);
+    }
+
+    #[test]
+    fn effect_with_false_condition_is_skipped() {
+        let mut state = new_match(1);
+        let condition = Condition {
+            left: Value::Own(Stat::Walls),
+            cmp: Comparison::Greater,
+            right: Value::Enemy(Stat::Walls),
+        };
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![
+                    CardEffect {
+                        condition: Some(condition),
+                        ..effect(Target::Enemy, EffectType::Damage(5, false))
+                    },
+                    CardEffect {
+                        condition: Some(condition),
+                        ..effect(Target::User, EffectType::PlayAgain)
+                    },
+                ],
+            ),
+        );
+        state.apply(&action).unwrap();
+
+        assert_eq!(
+            state.player(PlayerNumber::Second).health,
+            PlayerHealth::new(&state.rules)
+        );
+        assert_eq!(state.current, PlayerNumber::Second);
+    }
+
+    #[test]
+    fn effect_can_target_both_players() {
+        let mut state = new_match(1);
+        state.player_mut(PlayerNumber::First).health.walls_hp = 7;
+        let action = give(
+            &mut state,
+            card(
+                20,
+                0,
+                vec![effect(
+                    Target::Both,
+                    EffectType::ScaledDamage(Value::Own(Stat::Walls), false),
+                )],
+            ),
+        );
+        state.apply(&action).unwrap();
+
+        // both take 7 damage, the walls of the user are read before any of it is dealt
+        assert_eq!(state.player(PlayerNumber::First).health.walls_hp, 0);
+        assert_eq!(state.player(PlayerNumber::First).health.tower_hp, 15);
+        assert_eq!(state.player(PlayerNumber::Second).health.walls_hp, 0);
+        assert_eq!(state.player(PlayerNumber::Second).health.tower_hp, 13);
+    }
+
+    #[test]
+    fn small_deck_deals_smaller_hand() {
+        let small: Deck = vec![card(1, 0, vec![]), card(2, 0, vec![])].into();
+        let mut state = MatchState::new([small.clone(), small], 1, Ruleset::default());
+        let hand = state.current_player().hand.clone();
+        assert_eq!(hand.len(), 2);
+        assert!(state.current_player().piles.draw.is_empty());
+
+        // the dropped card is the only one left to draw
+        let card = hand[0].clone();
+        let events = state
+            .apply(&ActionTaken::DropCard { card: card.clone() })
+            .unwrap();
+        assert!(events.contains(&MatchEvent::CardDrawn {
+            player: PlayerNumber::First,
+            card
+        }));
+        assert_eq!(state.player(PlayerNumber::First).hand.len(), 2);
+    }
+}
@@ -0,0 +1,162 @@
+//! Messages exchanged between game clients and the match server.
+//!
+//! The server owns the [`MatchState`](crate::r#match::MatchState), clients only
+//! send the actions picked by their player and get back what they are allowed to see.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::data::{
+    card::Card,
+    deck::{Deck, DeckEntry},
+    hand::HandCards,
+    player::PlayerHealth,
+    player::PlayerNumber,
+    ruleset::Ruleset,
+    supply::PlayerSupply,
+};
+use crate::r#match::{ActionTaken, Events, MatchEvent, MatchOutcome, MatchView, RuleError};
+
+/// Bump when the messages change in a way older clients can't read.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// First message after connecting, the player waits for an opponent that
+    /// picked the same rules. The server builds the deck from its own cards.
+    Join {
+        version: u32,
+        name: String,
+        avatar_id: i32,
+        /// Name of the ruleset from `all.rules.json`
+        rules: String,
+        deck: Vec<DeckEntry>,
+    },
+    Action(ActionTaken),
+    /// The player gives up, the opponent wins.
+    Concede,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Both players joined, `you` is the seat of the receiving player.
+    MatchStarted {
+        you: PlayerNumber,
+        opponent: OpponentInfo,
+        state: MatchSnapshot,
+    },
+    /// Sent to both players after every accepted action.
+    StateSync {
+        events: Events,
+        state: MatchSnapshot,
+    },
+    Error(ProtocolError),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpponentInfo {
+    pub name: String,
+    pub avatar_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProtocolError {
+    VersionMismatch {
+        server: u32,
+        client: u32,
+    },
+    InvalidDeck(String),
+    UnknownRules(String),
+    /// Action was rejected, the match did not change.
+    Rule(RuleError),
+    UnexpectedMessage,
+    OpponentLeft,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::VersionMismatch { server, client } => write!(
+                f,
+                "server uses protocol version {} but the client uses {}",
+                server, client
+            ),
+            ProtocolError::InvalidDeck(reason) => write!(f, "invalid deck: {}", reason),
+            ProtocolError::UnknownRules(name) => write!(f, "server has no {} rules", name),
+            ProtocolError::Rule(err) => write!(f, "action rejected: {}", err),
+            ProtocolError::UnexpectedMessage => write!(f, "unexpected message"),
+            ProtocolError::OpponentLeft => write!(f, "opponent left the match"),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// Owned copy of the [`MatchView`] of one player, so it can be sent over the network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchSnapshot {
+    pub me: PlayerNumber,
+    pub current: PlayerNumber,
+    pub turn: u32,
+    pub health: PlayerHealth,
+    pub supply: PlayerSupply,
+    pub hand: HandCards,
+    pub enemy_health: PlayerHealth,
+    pub enemy_supply: PlayerSupply,
+    pub deck: Deck,
+    pub enemy_deck: Deck,
+    pub discard: Vec<Card>,
+    pub enemy_discard: Vec<Card>,
+    pub enemy_hand_size: usize,
+    pub rules: Ruleset,
+    pub outcome: Option<MatchOutcome>,
+}
+
+impl MatchSnapshot {
+    pub fn new(view: &MatchView, outcome: Option<MatchOutcome>) -> Self {
+        Self {
+            me: view.me,
+            current: view.current,
+            turn: view.turn,
+            health: *view.health,
+            supply: view.supply.clone(),
+            hand: view.hand.clone(),
+            enemy_health: *view.enemy_health,
+            enemy_supply: view.enemy_supply.clone(),
+            deck: view.deck.clone(),
+            enemy_deck: view.enemy_deck.clone(),
+            discard: view.discard.to_vec(),
+            enemy_discard: view.enemy_discard.to_vec(),
+            enemy_hand_size: view.enemy_hand_size,
+            rules: view.rules.clone(),
+            outcome,
+        }
+    }
+
+    pub fn view(&self) -> MatchView<'_> {
+        MatchView {
+            me: self.me,
+            current: self.current,
+            turn: self.turn,
+            health: &self.health,
+            supply: &self.supply,
+            hand: &self.hand,
+            enemy_health: &self.enemy_health,
+            enemy_supply: &self.enemy_supply,
+            deck: &self.deck,
+            enemy_deck: &self.enemy_deck,
+            discard: &self.discard,
+            enemy_discard: &self.enemy_discard,
+            enemy_hand_size: self.enemy_hand_size,
+            rules: &self.rules,
+        }
+    }
+}
+
+/// Events the given player is allowed to see, cards drawn by the enemy stay hidden.
+pub fn visible_events(events: &[MatchEvent], nr: PlayerNumber) -> Events {
+    events
+        .iter()
+        .filter(|event| !matches!(event, MatchEvent::CardDrawn { player, .. } if *player != nr))
+        .cloned()
+        .collect()
+}
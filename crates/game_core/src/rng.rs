@@ -0,0 +1,24 @@
+//! Deterministic random number generation shared by everything that needs
+//! to be reproducible from a match seed.
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+pub type GameRng = ChaCha8Rng;
+
+/// Independent streams derived from the same seed, so drawing for one
+/// purpose never shifts the numbers drawn for another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    /// Hand dealing and card draws inside [`crate::r#match::MatchState`]
+    Match,
+    /// Opponent name and avatar
+    Opponent,
+    /// AI decisions
+    Ai,
+}
+
+pub fn seeded(seed: u64, stream: RngStream) -> GameRng {
+    let mut rng = GameRng::seed_from_u64(seed);
+    rng.set_stream(stream as u64);
+    rng
+}
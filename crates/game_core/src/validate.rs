@@ -0,0 +1,283 @@
+//! Content checks for card decks, used by the `utils_deck_validator` tool.
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::data::{
+    card::{Card, EffectType, Target},
+    resource::ResourceType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckIssue {
+    DuplicateId(i32),
+    /// Ids should go from 1 to the amount of cards without gaps
+    MissingId(i32),
+    NoEffects(i32),
+    NoneEffect(i32),
+    /// Card changes production of the same resource of the same player more than once
+    RepeatedProductionChange {
+        id: i32,
+        resource: ResourceType,
+        target: Target,
+    },
+    NegativeCost(i32),
+    /// Card would never be put into the draw pile
+    NoCopies(i32),
+    MissingImage {
+        id: i32,
+        path: String,
+    },
+}
+
+impl DeckIssue {
+    pub fn card_id(&self) -> i32 {
+        match self {
+            DeckIssue::DuplicateId(id)
+            | DeckIssue::MissingId(id)
+            | DeckIssue::NoEffects(id)
+            | DeckIssue::NoneEffect(id)
+            | DeckIssue::NegativeCost(id)
+            | DeckIssue::NoCopies(id)
+            | DeckIssue::RepeatedProductionChange { id, .. }
+            | DeckIssue::MissingImage { id, .. } => *id,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            DeckIssue::DuplicateId(_)
+            | DeckIssue::NoEffects(_)
+            | DeckIssue::NegativeCost(_)
+            | DeckIssue::NoCopies(_)
+            | DeckIssue::MissingImage { .. } => Severity::Error,
+            DeckIssue::MissingId(_)
+            | DeckIssue::NoneEffect(_)
+            | DeckIssue::RepeatedProductionChange { .. } => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for DeckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckIssue::DuplicateId(id) => write!(f, "id {} is used by more than one card", id),
+            DeckIssue::MissingId(id) => write!(f, "id {} is missing, ids are not contiguous", id),
+            DeckIssue::NoEffects(id) => write!(f, "card {} has no effects", id),
+            DeckIssue::NoneEffect(id) => write!(f, "card {} has a `None` effect", id),
+            DeckIssue::RepeatedProductionChange {
+                id,
+                resource,
+                target,
+            } => write!(
+                f,
+                "card {} changes {} production of the {} more than once",
+                id,
+                resource,
+                match target {
+                    Target::User => "user",
+                    Target::Enemy => "enemy",
+                    Target::Both => "both players",
+                }
+            ),
+            DeckIssue::NegativeCost(id) => write!(f, "card {} has a negative cost", id),
+            DeckIssue::NoCopies(id) => write!(f, "card {} has 0 copies", id),
+            DeckIssue::MissingImage { id, path } => {
+                write!(f, "card {} has no image, expected {}", id, path)
+            }
+        }
+    }
+}
+
+/// Checks the deck content, issues are sorted by the card id.
+pub fn validate_deck(cards: &[Card]) -> Vec<DeckIssue> {
+    let mut issues = Vec::new();
+    let mut ids: HashMap<i32, usize> = HashMap::new();
+    for card in cards {
+        *ids.entry(card.id).or_default() += 1;
+    }
+    for (id, count) in ids.iter() {
+        if *count > 1 {
+            issues.push(DeckIssue::DuplicateId(*id));
+        }
+    }
+    let max_id = ids.keys().copied().max().unwrap_or(0);
+    for id in 1..=max_id.max(cards.len() as i32) {
+        if !ids.contains_key(&id) {
+            issues.push(DeckIssue::MissingId(id));
+        }
+    }
+
+    for card in cards {
+        if card.effects.is_empty() {
+            issues.push(DeckIssue::NoEffects(card.id));
+        }
+        if card
+            .effects
+            .iter()
+            .any(|e| e.effect_type == EffectType::None)
+        {
+            issues.push(DeckIssue::NoneEffect(card.id));
+        }
+        if card.cost_amount < 0 {
+            issues.push(DeckIssue::NegativeCost(card.id));
+        }
+        if card.copies == 0 {
+            issues.push(DeckIssue::NoCopies(card.id));
+        }
+        let mut production: HashMap<(ResourceType, Target), usize> = HashMap::new();
+        for effect in card.effects.iter() {
+            if let EffectType::ProductionChange(resource, _) = effect.effect_type {
+                *production.entry((resource, effect.target)).or_default() += 1;
+            }
+        }
+        for ((resource, target), count) in production {
+            if count > 1 {
+                issues.push(DeckIssue::RepeatedProductionChange {
+                    id: card.id,
+                    resource,
+                    target,
+                });
+            }
+        }
+    }
+    issues.sort_by_key(DeckIssue::card_id);
+    issues
+}
+
+/// Reports cards for which `exists` returns false for the image path relative to the assets directory.
+pub fn find_missing_images(cards: &[Card], exists: impl Fn(&str) -> bool) -> Vec<DeckIssue> {
+    cards
+        .iter()
+        .map(|card| (card.id, card.get_image_asset()))
+        .filter(|(_, path)| !exists(path))
+        .map(|(id, path)| DeckIssue::MissingImage { id, path })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::card::CardEffect;
+
+    fn card(id: i32, effects: Vec<EffectType>) -> Card {
+        Card {
+            id,
+            effects: effects
+                .into_iter()
+                .map(|effect_type| CardEffect {
+                    effect_type,
+                    ..CardEffect::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn damage(id: i32) -> Card {
+        card(id, vec![EffectType::Damage(1, false)])
+    }
+
+    #[test]
+    fn valid_deck_has_no_issues() {
+        let cards: Vec<Card> =
+            serde_json::from_str(include_str!("../../../assets/all.deck.json")).unwrap();
+        assert_eq!(validate_deck(&cards), []);
+        assert_eq!(validate_deck(&[damage(1), damage(2)]), []);
+    }
+
+    #[test]
+    fn duplicate_id_is_error() {
+        let issues = validate_deck(&[damage(1), damage(1)]);
+        assert_eq!(issues, [DeckIssue::DuplicateId(1), DeckIssue::MissingId(2)]);
+        assert_eq!(issues[0].severity(), Severity::Error);
+    }
+
+    #[test]
+    fn missing_id_is_warning() {
+        let issues = validate_deck(&[damage(1), damage(3)]);
+        assert_eq!(issues, [DeckIssue::MissingId(2)]);
+        assert_eq!(issues[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn card_without_effects_is_error() {
+        let issues = validate_deck(&[card(1, vec![])]);
+        assert_eq!(issues, [DeckIssue::NoEffects(1)]);
+        assert_eq!(issues[0].severity(), Severity::Error);
+    }
+
+    #[test]
+    fn none_effect_is_warning() {
+        let issues = validate_deck(&[card(1, vec![EffectType::None])]);
+        assert_eq!(issues, [DeckIssue::NoneEffect(1)]);
+        assert_eq!(issues[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn repeated_production_change_is_warning() {
+        let change = EffectType::ProductionChange(ResourceType::Magic, 1);
+        let mut repeated = card(1, vec![change, change]);
+        let issues = validate_deck(std::slice::from_ref(&repeated));
+        assert_eq!(
+            issues,
+            [DeckIssue::RepeatedProductionChange {
+                id: 1,
+                resource: ResourceType::Magic,
+                target: Target::Enemy,
+            }]
+        );
+        assert_eq!(issues[0].severity(), Severity::Warning);
+
+        // the same change for other players is fine
+        repeated.effects[1].target = Target::User;
+        assert_eq!(validate_deck(&[repeated]), []);
+    }
+
+    #[test]
+    fn negative_cost_is_error() {
+        let cheap = Card {
+            cost_amount: -1,
+            ..damage(1)
+        };
+        let issues = validate_deck(&[cheap]);
+        assert_eq!(issues, [DeckIssue::NegativeCost(1)]);
+        assert_eq!(issues[0].severity(), Severity::Error);
+    }
+
+    #[test]
+    fn card_without_copies_is_error() {
+        let unused = Card {
+            copies: 0,
+            ..damage(1)
+        };
+        let issues = validate_deck(&[unused]);
+        assert_eq!(issues, [DeckIssue::NoCopies(1)]);
+        assert_eq!(issues[0].severity(), Severity::Error);
+    }
+
+    #[test]
+    fn issues_are_sorted_by_card_id() {
+        let issues = validate_deck(&[card(3, vec![]), damage(1), card(2, vec![EffectType::None])]);
+        let ids: Vec<i32> = issues.iter().map(DeckIssue::card_id).collect();
+        assert_eq!(ids, [2, 3]);
+    }
+
+    #[test]
+    fn missing_image_is_error() {
+        let issues = find_missing_images(&[damage(1), damage(2)], |path| path == "cards/1.png");
+        assert_eq!(
+            issues,
+            [DeckIssue::MissingImage {
+                id: 2,
+                path: "cards/2.png".to_owned()
+            }]
+        );
+        assert_eq!(issues[0].severity(), Severity::Error);
+    }
+}
@@ -0,0 +1,67 @@
+//! Blocking client for headless players, like bots or the example matches.
+use crate::encode;
+use game_core::protocol::{ClientMessage, ServerMessage};
+use std::fmt;
+use std::net::TcpStream;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+#[derive(Debug)]
+pub enum ClientError {
+    Connection(Box<tungstenite::Error>),
+    InvalidMessage(serde_json::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Connection(err) => write!(f, "connection error: {}", err),
+            ClientError::InvalidMessage(err) => write!(f, "invalid server message: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<tungstenite::Error> for ClientError {
+    fn from(value: tungstenite::Error) -> Self {
+        ClientError::Connection(Box::new(value))
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(value: serde_json::Error) -> Self {
+        ClientError::InvalidMessage(value)
+    }
+}
+
+pub struct Client {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+impl Client {
+    /// Connects to the server, `url` looks like `ws://127.0.0.1:9001`.
+    pub fn connect(url: &str) -> Result<Self, ClientError> {
+        let (socket, _) = tungstenite::connect(url)?;
+        Ok(Self { socket })
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> Result<(), ClientError> {
+        self.socket.send(encode(message))?;
+        Ok(())
+    }
+
+    /// Blocks until the next message from the server arrives.
+    pub fn receive(&mut self) -> Result<ServerMessage, ClientError> {
+        loop {
+            if let Message::Text(text) = self.socket.read()? {
+                return Ok(serde_json::from_str(&text)?);
+            }
+        }
+    }
+
+    pub fn close(mut self) {
+        let _ = self.socket.close(None);
+        while self.socket.read().is_ok() {}
+    }
+}
@@ -0,0 +1,194 @@
+//! Server hosting matches between remote players.
+//!
+//! Every connection has to send [`ClientMessage::Join`] first, joined players
+//! are paired in the order they arrive with someone that picked the same rules
+//! and every pair plays in its own thread.
+use game_core::consts;
+use game_core::data::card::Card;
+use game_core::data::deck::{Deck, DeckEntry};
+use game_core::data::ruleset::{Ruleset, RulesetsAsset};
+use game_core::protocol::{ClientMessage, PROTOCOL_VERSION, ProtocolError, ServerMessage};
+use game_core::validate::{self, Severity};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+pub mod client;
+mod session;
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:9001";
+pub const DEFAULT_ASSETS: &str = "assets";
+const JOIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Socket = WebSocket<TcpStream>;
+
+/// Player that joined and waits for the opponent.
+struct Seat {
+    socket: Socket,
+    name: String,
+    avatar_id: i32,
+    rules: Ruleset,
+    deck: Deck,
+}
+
+/// Cards and rulesets matches are built from, clients refer to them only by id and name.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    pub cards: Vec<Card>,
+    pub rulesets: Vec<Ruleset>,
+}
+
+impl Catalog {
+    /// Reads `all.deck.json` and `all.rules.json` from the assets directory.
+    pub fn load(assets: &Path) -> Result<Self, Box<dyn Error>> {
+        let cards = std::fs::read_to_string(assets.join("all.deck.json"))?;
+        let rulesets = std::fs::read_to_string(assets.join("all.rules.json"))?;
+        Ok(Self {
+            cards: serde_json::from_str(&cards)?,
+            rulesets: serde_json::from_str::<RulesetsAsset>(&rulesets)?.0,
+        })
+    }
+}
+
+/// Accepts connections until the listener fails.
+pub fn serve(listener: TcpListener, catalog: Catalog) -> std::io::Result<()> {
+    let catalog = Arc::new(catalog);
+    let (seats_tx, seats_rx) = mpsc::channel();
+    thread::spawn(move || pair_players(seats_rx));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        let seats = seats_tx.clone();
+        let catalog = catalog.clone();
+        thread::spawn(move || match join(stream, &catalog) {
+            Ok(seat) => {
+                println!("{} joined with {} rules", seat.name, seat.rules.name);
+                let _ = seats.send(seat);
+            }
+            Err(err) => eprintln!("Connection rejected: {}", err),
+        });
+    }
+    Ok(())
+}
+
+fn pair_players(seats: mpsc::Receiver<Seat>) {
+    // players waiting for an opponent, by the name of the rules
+    let mut waiting: HashMap<String, Seat> = HashMap::new();
+    while let Ok(seat) = seats.recv() {
+        match waiting.remove(&seat.rules.name) {
+            Some(first) => {
+                thread::spawn(move || session::run([first, seat]));
+            }
+            None => {
+                waiting.insert(seat.rules.name.clone(), seat);
+            }
+        }
+    }
+}
+
+fn join(stream: TcpStream, catalog: &Catalog) -> Result<Seat, Box<dyn Error>> {
+    stream.set_read_timeout(Some(JOIN_TIMEOUT))?;
+    let mut socket = tungstenite::accept(stream)?;
+    let message = loop {
+        if let Message::Text(text) = socket.read()? {
+            break serde_json::from_str::<ClientMessage>(&text).ok();
+        }
+    };
+    let Some(ClientMessage::Join {
+        version,
+        name,
+        avatar_id,
+        rules,
+        deck,
+    }) = message
+    else {
+        return Err(reject(&mut socket, ProtocolError::UnexpectedMessage));
+    };
+    if version != PROTOCOL_VERSION {
+        let error = ProtocolError::VersionMismatch {
+            server: PROTOCOL_VERSION,
+            client: version,
+        };
+        return Err(reject(&mut socket, error));
+    }
+    let Some(rules) = catalog.rulesets.iter().find(|r| r.name == rules).cloned() else {
+        return Err(reject(&mut socket, ProtocolError::UnknownRules(rules)));
+    };
+    let deck = match build_deck(&deck, &catalog.cards) {
+        Ok(deck) => deck,
+        Err(reason) => return Err(reject(&mut socket, ProtocolError::InvalidDeck(reason))),
+    };
+    socket.get_ref().set_read_timeout(None)?;
+    Ok(Seat {
+        socket,
+        name,
+        avatar_id,
+        rules,
+        deck,
+    })
+}
+
+/// Builds the deck from the server cards, so clients can't send cards of their own.
+fn build_deck(entries: &[DeckEntry], cards: &[Card]) -> Result<Deck, String> {
+    let size: u32 = entries.iter().map(|e| e.copies).sum();
+    if !(consts::MIN_DECK_SIZE..=consts::MAX_DECK_SIZE).contains(&size) {
+        return Err(format!(
+            "deck has {} cards, it needs between {} and {}",
+            size,
+            consts::MIN_DECK_SIZE,
+            consts::MAX_DECK_SIZE
+        ));
+    }
+    let mut deck = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        if entries[..i].iter().any(|e| e.id == entry.id) {
+            return Err(format!("card {} is listed more than once", entry.id));
+        }
+        if entry.copies > consts::MAX_CARD_COPIES {
+            return Err(format!(
+                "card {} has {} copies, at most {} are allowed",
+                entry.id,
+                entry.copies,
+                consts::MAX_CARD_COPIES
+            ));
+        }
+        let Some(card) = cards.iter().find(|c| c.id == entry.id) else {
+            return Err(format!("there is no card with id {}", entry.id));
+        };
+        if entry.copies > 0 {
+            deck.push(Card {
+                copies: entry.copies,
+                ..card.clone()
+            });
+        }
+    }
+    match validate::validate_deck(&deck)
+        .into_iter()
+        .find(|issue| issue.severity() == Severity::Error)
+    {
+        Some(issue) => Err(issue.to_string()),
+        None => Ok(deck.into()),
+    }
+}
+
+/// Sends the error to the client before dropping the connection.
+fn reject(socket: &mut Socket, error: ProtocolError) -> Box<dyn Error> {
+    let _ = socket.send(encode(&ServerMessage::Error(error.clone())));
+    let _ = socket.close(None);
+    Box::new(error)
+}
+
+fn encode(message: &impl Serialize) -> Message {
+    Message::text(serde_json::to_string(message).expect("Messages are always serializable"))
+}
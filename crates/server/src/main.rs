@@ -0,0 +1,59 @@
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const HELP: &str = "Usage: server [--address <host:port>] [--assets <dir>]
+
+Hosts matches for players connecting over WebSocket, pairing them in the order they join.
+Cards and rulesets are read from all.deck.json and all.rules.json in the assets directory.";
+
+fn main() -> ExitCode {
+    let mut address = server::DEFAULT_ADDRESS.to_owned();
+    let mut assets = PathBuf::from(server::DEFAULT_ASSETS);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--address" => match args.next() {
+                Some(value) => address = value,
+                None => {
+                    eprintln!("--address needs a value");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--assets" => match args.next() {
+                Some(value) => assets = value.into(),
+                None => {
+                    eprintln!("--assets needs a value");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => {
+                eprintln!("{}", HELP);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let catalog = match server::Catalog::load(&assets) {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            eprintln!("Failed to load assets from {}: {}", assets.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Failed to listen on {}: {}", address, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Listening on ws://{}", address);
+    match server::serve(listener, catalog) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
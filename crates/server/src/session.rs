@@ -0,0 +1,127 @@
+use crate::{Seat, Socket, encode};
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::MatchState;
+use game_core::protocol::{
+    self, ClientMessage, MatchSnapshot, OpponentInfo, ProtocolError, ServerMessage,
+};
+use std::io::ErrorKind;
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Error, Message};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Plays the whole match, returns once it is finished or one of the players left.
+pub(crate) fn run(seats: [Seat; 2]) {
+    let decks = seats.each_ref().map(|seat| seat.deck.clone());
+    let rules = seats[0].rules.clone();
+    let mut state = MatchState::new(decks, rand::random(), rules);
+    println!(
+        "Match started: {} vs {}, {} rules, seed {}",
+        seats[0].name, seats[1].name, state.rules.name, state.seed
+    );
+    let opponents = seats.each_ref().map(|seat| OpponentInfo {
+        name: seat.name.clone(),
+        avatar_id: seat.avatar_id,
+    });
+    let mut sockets = seats.map(|seat| seat.socket);
+    for nr in PlayerNumber::ALL {
+        let message = ServerMessage::MatchStarted {
+            you: nr,
+            opponent: opponents[nr.other().index()].clone(),
+            state: snapshot(&state, nr),
+        };
+        if sockets[nr.index()].send(encode(&message)).is_err() {
+            return leave(&mut sockets, nr);
+        }
+    }
+    for socket in sockets.iter() {
+        if socket.get_ref().set_nonblocking(true).is_err() {
+            return;
+        }
+    }
+
+    while !state.is_finished() {
+        let mut idle = true;
+        for nr in PlayerNumber::ALL {
+            let socket = &mut sockets[nr.index()];
+            let text = match socket.read() {
+                Ok(Message::Text(text)) => text,
+                Ok(_) => continue,
+                Err(Error::Io(err)) if err.kind() == ErrorKind::WouldBlock => continue,
+                Err(_) => return leave(&mut sockets, nr),
+            };
+            idle = false;
+            let result = match serde_json::from_str(&text) {
+                Ok(ClientMessage::Action(action)) => state.apply_as(nr, &action),
+                Ok(ClientMessage::Concede) => state.concede(nr),
+                _ => {
+                    let _ = socket.write(encode(&ServerMessage::Error(
+                        ProtocolError::UnexpectedMessage,
+                    )));
+                    continue;
+                }
+            };
+            match result {
+                Ok(events) => {
+                    for receiver in PlayerNumber::ALL {
+                        let message = ServerMessage::StateSync {
+                            events: protocol::visible_events(&events, receiver),
+                            state: snapshot(&state, receiver),
+                        };
+                        let _ = sockets[receiver.index()].write(encode(&message));
+                    }
+                }
+                Err(err) => {
+                    let message = ServerMessage::Error(ProtocolError::Rule(err));
+                    let _ = socket.write(encode(&message));
+                }
+            }
+        }
+        for nr in PlayerNumber::ALL {
+            match sockets[nr.index()].flush() {
+                Ok(()) => {}
+                Err(Error::Io(err)) if err.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => return leave(&mut sockets, nr),
+            }
+        }
+        if idle {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    if let Some(outcome) = state.outcome() {
+        println!(
+            "Match finished: {:?} won, {:?}",
+            outcome.winner, outcome.reason
+        );
+    }
+    for socket in sockets.iter_mut() {
+        close(socket);
+    }
+}
+
+fn snapshot(state: &MatchState, nr: PlayerNumber) -> MatchSnapshot {
+    MatchSnapshot::new(&state.view(nr), state.outcome())
+}
+
+/// Tells the player that stayed that the match is over.
+fn leave(sockets: &mut [Socket; 2], left: PlayerNumber) {
+    println!("{:?} player left the match", left);
+    let socket = &mut sockets[left.other().index()];
+    let _ = socket.write(encode(&ServerMessage::Error(ProtocolError::OpponentLeft)));
+    close(socket);
+}
+
+/// Sends everything that is still queued and waits for the client to close the connection.
+fn close(socket: &mut Socket) {
+    let stream = socket.get_ref();
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_read_timeout(Some(CLOSE_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let _ = socket.close(None);
+    while socket.read().is_ok() {}
+}
@@ -0,0 +1,168 @@
+//! Starts the server on localhost and lets two AI clients play a full match over it.
+use game_core::ai::AiDifficulty;
+use game_core::data::deck::DeckEntry;
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::{MatchOutcome, WinReason};
+use game_core::protocol::{ClientMessage, PROTOCOL_VERSION, ProtocolError, ServerMessage};
+use game_core::rng::{self, RngStream};
+use server::Catalog;
+use server::client::Client;
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+
+fn catalog() -> Catalog {
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    Catalog::load(&assets).expect("Cannot load the assets")
+}
+
+/// Every card of the catalog with its default copies.
+fn full_deck() -> Vec<DeckEntry> {
+    catalog()
+        .cards
+        .iter()
+        .map(|card| DeckEntry {
+            id: card.id,
+            copies: card.copies,
+        })
+        .collect()
+}
+
+/// Starts the server on a free port and returns its url.
+fn start_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Cannot bind the listener");
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    thread::spawn(move || server::serve(listener, catalog()));
+    url
+}
+
+fn join(url: &str, name: &str, rules: &str, deck: Vec<DeckEntry>) -> Client {
+    let mut client = Client::connect(url).expect("Cannot connect");
+    client
+        .send(&ClientMessage::Join {
+            version: PROTOCOL_VERSION,
+            name: name.to_owned(),
+            avatar_id: 1,
+            rules: rules.to_owned(),
+            deck,
+        })
+        .expect("Cannot send the join message");
+    client
+}
+
+#[test]
+fn clients_agree_on_outcome() {
+    let url = start_server();
+
+    let players = [
+        ("Mieszko", AiDifficulty::Normal),
+        ("Dobrawa", AiDifficulty::Easy),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (name, difficulty))| {
+        let url = url.clone();
+        thread::spawn(move || play(&url, name, difficulty, i as u64))
+    })
+    .collect::<Vec<_>>();
+    let results: Vec<(PlayerNumber, MatchOutcome)> = players
+        .into_iter()
+        .map(|player| {
+            player
+                .join()
+                .expect("Client thread panicked")
+                .expect("Client failed")
+        })
+        .collect();
+    assert_ne!(results[0].0, results[1].0, "Clients got the same seat");
+    assert_eq!(results[0].1, results[1].1);
+}
+
+fn play(
+    url: &str,
+    name: &str,
+    difficulty: AiDifficulty,
+    seed: u64,
+) -> Result<(PlayerNumber, MatchOutcome), String> {
+    let mut client = join(url, name, "Classic", full_deck());
+    let mut policy = difficulty.policy();
+    let mut rng = rng::seeded(seed, RngStream::Ai);
+    loop {
+        let state = match client.receive().map_err(|e| e.to_string())? {
+            ServerMessage::MatchStarted { state, .. } | ServerMessage::StateSync { state, .. } => {
+                state
+            }
+            ServerMessage::Error(err) => return Err(err.to_string()),
+        };
+        if let Some(outcome) = state.outcome {
+            client.close();
+            return Ok((state.me, outcome));
+        }
+        if state.current == state.me {
+            let action = policy
+                .select_action(&state.view(), &mut rng)
+                .ok_or("Nothing to play with an empty hand")?;
+            client
+                .send(&ClientMessage::Action(action))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+}
+
+#[test]
+fn conceding_ends_match_for_both() {
+    let url = start_server();
+    let mut clients = [
+        join(&url, "Mieszko", "Quick", full_deck()),
+        join(&url, "Dobrawa", "Quick", full_deck()),
+    ];
+    let mut seats = Vec::new();
+    for client in clients.iter_mut() {
+        let Ok(ServerMessage::MatchStarted { you, state, .. }) = client.receive() else {
+            panic!("Match did not start");
+        };
+        assert_eq!(state.rules.name, "Quick");
+        seats.push(you);
+    }
+    clients[0].send(&ClientMessage::Concede).unwrap();
+
+    let outcome = MatchOutcome {
+        winner: seats[1],
+        reason: WinReason::Conceded,
+    };
+    for client in clients.iter_mut() {
+        let Ok(ServerMessage::StateSync { state, .. }) = client.receive() else {
+            panic!("Concession was not synced");
+        };
+        assert_eq!(state.outcome, Some(outcome));
+    }
+}
+
+#[test]
+fn rejects_decks_breaking_limits() {
+    let url = start_server();
+    let id = full_deck()[0].id;
+    let too_many_copies = [DeckEntry { id, copies: 4 }]
+        .into_iter()
+        .chain(full_deck().into_iter().skip(1))
+        .collect();
+    let unknown_card = [DeckEntry { id: 999, copies: 1 }]
+        .into_iter()
+        .chain(full_deck())
+        .collect();
+    let too_small = full_deck().into_iter().take(5).collect();
+    let too_big = full_deck().into_iter().chain(full_deck()).collect();
+
+    for deck in [too_many_copies, unknown_card, too_small, too_big] {
+        let mut client = join(&url, "Mieszko", "Classic", deck);
+        assert!(matches!(
+            client.receive(),
+            Ok(ServerMessage::Error(ProtocolError::InvalidDeck(_)))
+        ));
+    }
+    let mut client = join(&url, "Mieszko", "Blitz", full_deck());
+    assert!(matches!(
+        client.receive(),
+        Ok(ServerMessage::Error(ProtocolError::UnknownRules(name))) if name == "Blitz"
+    ));
+}
@@ -0,0 +1,194 @@
+use game_core::data::card::Card;
+use game_core::data::deck::Deck;
+use game_core::data::ruleset::{Ruleset, RulesetsAsset};
+use play::{MatchResult, MatchSetup};
+use policy::PolicySpec;
+use report::Report;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+
+mod play;
+mod policy;
+mod report;
+
+const HELP: &str = "Usage: sim [--matches <n>] [--seed <n>] [--deck <file>] [--rules <name>] [--rules-file <file>] [--max-turns <n>] [--threads <n>] [--json <file>] [--csv <dir>] [POLICY...]
+
+Plays seeded matches between every pair of policies and reports the results.
+Policies: easy, normal, hard, expert, trained or bot:<command> for an external bot, easy normal hard by default.
+Every pair plays --matches matches (1000 by default) swapping the seats after every match.
+Prints the JSON report when neither --json nor --csv is given.";
+
+struct Args {
+    matches: usize,
+    seed: u64,
+    deck: PathBuf,
+    rules_file: PathBuf,
+    rules: Option<String>,
+    max_turns: u32,
+    threads: usize,
+    json: Option<PathBuf>,
+    csv: Option<PathBuf>,
+    policies: Vec<PolicySpec>,
+}
+
+fn parse_value<T: std::str::FromStr>(value: Option<String>, arg: &str) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", arg))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", arg, value))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        matches: 1000,
+        seed: 0,
+        deck: PathBuf::from("assets/all.deck.json"),
+        rules_file: PathBuf::from("assets/all.rules.json"),
+        rules: None,
+        max_turns: 1000,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        json: None,
+        csv: None,
+        policies: Vec::new(),
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--matches" => args.matches = parse_value(iter.next(), &arg)?,
+            "--seed" => args.seed = parse_value(iter.next(), &arg)?,
+            "--deck" => args.deck = parse_value(iter.next(), &arg)?,
+            "--rules-file" => args.rules_file = parse_value(iter.next(), &arg)?,
+            "--rules" => args.rules = Some(parse_value(iter.next(), &arg)?),
+            "--max-turns" => args.max_turns = parse_value(iter.next(), &arg)?,
+            "--threads" => args.threads = parse_value::<usize>(iter.next(), &arg)?.max(1),
+            "--json" => args.json = Some(parse_value(iter.next(), &arg)?),
+            "--csv" => args.csv = Some(parse_value(iter.next(), &arg)?),
+            "-h" | "--help" => return Err(HELP.to_owned()),
+            _ => args.policies.push(PolicySpec::parse(&arg)?),
+        }
+    }
+    if args.policies.is_empty() {
+        args.policies = ["easy", "normal", "hard"]
+            .into_iter()
+            .map(PolicySpec::parse)
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(args)
+}
+
+fn load_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn load_rules(args: &Args) -> Result<Ruleset, String> {
+    let Some(name) = &args.rules else {
+        return Ok(Ruleset::default());
+    };
+    let rulesets: RulesetsAsset = load_json(&args.rules_file)?;
+    rulesets
+        .0
+        .into_iter()
+        .find(|r| r.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("No ruleset named `{}`", name))
+}
+
+/// Seats of every match, each pair of policies plays the same amount of matches from both seats.
+fn schedule(policies: usize, matches: usize) -> Vec<[usize; 2]> {
+    let mut pairs = Vec::new();
+    for a in 0..policies {
+        for b in a + 1..policies {
+            pairs.push([a, b]);
+        }
+    }
+    if pairs.is_empty() {
+        pairs.push([0, 0]);
+    }
+    pairs
+        .into_iter()
+        .flat_map(|[a, b]| (0..matches).map(move |i| if i % 2 == 0 { [a, b] } else { [b, a] }))
+        .collect()
+}
+
+fn run(args: &Args) -> Result<Report, String> {
+    let cards: Vec<Card> = load_json(&args.deck)?;
+    let deck = Deck::from(cards.clone());
+    let rules = load_rules(args)?;
+    let setup = MatchSetup {
+        policies: &args.policies,
+        deck: &deck,
+        rules: &rules,
+        max_turns: args.max_turns,
+    };
+    let seats = schedule(args.policies.len(), args.matches);
+    let chunk = seats.len().div_ceil(args.threads).max(1);
+    // every match has its own seed, so splitting them between threads doesn't change the results
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = seats
+            .chunks(chunk)
+            .enumerate()
+            .map(|(c, seats)| {
+                let setup = &setup;
+                scope.spawn(move || {
+                    seats
+                        .iter()
+                        .enumerate()
+                        .map(|(i, seats)| {
+                            setup.play(*seats, args.seed.wrapping_add((c * chunk + i) as u64))
+                        })
+                        .collect::<Result<Vec<MatchResult>, String>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| {
+                h.join()
+                    .map_err(|_| "Simulation thread panicked".to_owned())?
+            })
+            .collect::<Result<Vec<_>, String>>()
+    })?
+    .concat();
+    Ok(Report::new(
+        &args.policies,
+        &cards,
+        args.seed,
+        rules.name,
+        &results,
+    ))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    let report = match run(&args) {
+        Ok(report) => report,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    let json = serde_json::to_string_pretty(&report).expect("Report is always serializable");
+    let written = match (&args.json, &args.csv) {
+        (None, None) => {
+            println!("{}", json);
+            Ok(())
+        }
+        (json_path, csv_dir) => json_path
+            .iter()
+            .try_for_each(|path| std::fs::write(path, &json))
+            .and_then(|_| csv_dir.iter().try_for_each(|dir| report.write_csv(dir))),
+    };
+    if let Err(err) = written {
+        eprintln!("Failed to write the report: {}", err);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,90 @@
+use crate::policy::PolicySpec;
+use game_core::data::deck::Deck;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
+use game_core::r#match::{MatchEvent, MatchOutcome, MatchState};
+use game_core::rng::{self, RngStream};
+
+/// Everything about a single match needed for the report.
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    /// Index of the policy in every seat
+    pub seats: [usize; 2],
+    /// `None` when the match was stopped after the turn limit
+    pub outcome: Option<MatchOutcome>,
+    pub turns: u32,
+    /// Ids of the cards used by every seat
+    pub used: [Vec<i32>; 2],
+    /// Ids of the cards dropped by every seat
+    pub dropped: [Vec<i32>; 2],
+}
+
+pub struct MatchSetup<'a> {
+    pub policies: &'a [PolicySpec],
+    pub deck: &'a Deck,
+    pub rules: &'a Ruleset,
+    pub max_turns: u32,
+}
+
+impl MatchSetup<'_> {
+    pub fn play(&self, seats: [usize; 2], seed: u64) -> Result<MatchResult, String> {
+        let mut policies = [
+            self.policies[seats[0]].build()?,
+            self.policies[seats[1]].build()?,
+        ];
+        let mut state = MatchState::new(
+            [self.deck.clone(), self.deck.clone()],
+            seed,
+            self.rules.clone(),
+        );
+        let mut rng = rng::seeded(seed, RngStream::Ai);
+        let mut result = MatchResult {
+            seats,
+            outcome: None,
+            turns: 0,
+            used: Default::default(),
+            dropped: Default::default(),
+        };
+        while !state.is_finished() && state.turn < self.max_turns {
+            let current = state.current;
+            let Some(action) =
+                policies[current.index()].select_action(&state.view(current), &mut rng)
+            else {
+                // without cards there is nothing to play, the player gives up
+                let _ = state.concede(current);
+                break;
+            };
+            let events = state.apply(&action).map_err(|err| {
+                format!(
+                    "{} picked an illegal action: {}",
+                    self.policies[seats[current.index()]],
+                    err
+                )
+            })?;
+            for event in events {
+                match event {
+                    MatchEvent::CardUsed { player, card } => {
+                        result.used[player.index()].push(card.id)
+                    }
+                    MatchEvent::CardDropped { player, card } => {
+                        result.dropped[player.index()].push(card.id)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        result.turns = state.turn;
+        result.outcome = state.outcome();
+        Ok(result)
+    }
+}
+
+impl MatchResult {
+    pub fn winner_policy(&self) -> Option<usize> {
+        self.outcome.map(|o| self.seats[o.winner.index()])
+    }
+
+    pub fn won(&self, nr: PlayerNumber) -> bool {
+        self.outcome.is_some_and(|o| o.winner == nr)
+    }
+}
@@ -0,0 +1,50 @@
+use game_core::ai::external::ExternalBot;
+use game_core::ai::{AiDifficulty, AiPolicy, Budget, MonteCarlo};
+use std::fmt;
+
+/// Policy picked on the command line, built again for every match.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicySpec {
+    Difficulty(AiDifficulty),
+    /// Command starting an external bot
+    Bot(String),
+}
+
+impl PolicySpec {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if let Some(command) = value.strip_prefix("bot:") {
+            return Ok(PolicySpec::Bot(command.to_owned()));
+        }
+        AiDifficulty::ALL
+            .into_iter()
+            .find(|d| d.to_string().eq_ignore_ascii_case(value))
+            .map(PolicySpec::Difficulty)
+            .ok_or_else(|| format!("Unknown policy `{}`", value))
+    }
+
+    pub fn build(&self) -> Result<Box<dyn AiPolicy>, String> {
+        match self {
+            // without the time limit, so the results only depend on the seed
+            PolicySpec::Difficulty(AiDifficulty::Expert) => Ok(Box::new(MonteCarlo {
+                budget: Budget {
+                    time: None,
+                    ..Budget::default()
+                },
+                ..MonteCarlo::default()
+            })),
+            PolicySpec::Difficulty(difficulty) => Ok(difficulty.policy()),
+            PolicySpec::Bot(command) => ExternalBot::spawn(command)
+                .map(|bot| Box::new(bot) as Box<dyn AiPolicy>)
+                .map_err(|err| format!("Failed to start bot `{}`: {}", command, err)),
+        }
+    }
+}
+
+impl fmt::Display for PolicySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicySpec::Difficulty(difficulty) => write!(f, "{}", difficulty),
+            PolicySpec::Bot(command) => write!(f, "bot:{}", command),
+        }
+    }
+}
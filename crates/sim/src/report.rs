@@ -0,0 +1,313 @@
+use crate::play::MatchResult;
+use crate::policy::PolicySpec;
+use game_core::data::card::Card;
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::WinReason;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub seed: u64,
+    pub rules: String,
+    pub matches: usize,
+    /// Matches stopped after the turn limit, they are left out of the win rates
+    pub unfinished: usize,
+    pub average_turns: f64,
+    pub end_reasons: EndReasons,
+    pub first_player: FirstPlayer,
+    pub policies: Vec<PolicyStats>,
+    pub matchups: Vec<MatchupStats>,
+    pub cards: Vec<CardStats>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EndReasons {
+    pub tower_destroyed: usize,
+    pub tower_completed: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FirstPlayer {
+    pub wins: usize,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PolicyStats {
+    pub policy: String,
+    pub matches: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MatchupStats {
+    pub first: String,
+    pub second: String,
+    pub matches: usize,
+    pub first_wins: usize,
+    pub second_wins: usize,
+    pub unfinished: usize,
+    pub average_turns: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CardStats {
+    pub id: i32,
+    pub name: String,
+    pub used: usize,
+    pub discarded: usize,
+    /// Win rate of the players that used the card at least once
+    pub win_rate_when_used: f64,
+    pub win_rate_when_not_used: f64,
+    /// Difference between the two win rates, positive when using the card goes with winning
+    pub win_delta: f64,
+}
+
+#[derive(Default)]
+struct CardCounter {
+    used: usize,
+    discarded: usize,
+    used_in: usize,
+    won_used: usize,
+    not_used_in: usize,
+    won_not_used: usize,
+}
+
+fn rate(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+impl Report {
+    pub fn new(
+        policies: &[PolicySpec],
+        cards: &[Card],
+        seed: u64,
+        rules: String,
+        results: &[MatchResult],
+    ) -> Self {
+        let finished: Vec<_> = results.iter().filter(|r| r.outcome.is_some()).collect();
+        let mut end_reasons = EndReasons::default();
+        for result in &finished {
+            match result.outcome.map(|o| o.reason) {
+                Some(WinReason::TowerDestroyed) => end_reasons.tower_destroyed += 1,
+                Some(WinReason::TowerCompleted) => end_reasons.tower_completed += 1,
+                // policies never give up
+                Some(WinReason::Conceded) | None => {}
+            }
+        }
+        let first_wins = finished
+            .iter()
+            .filter(|r| r.won(PlayerNumber::First))
+            .count();
+
+        let mut policy_stats: Vec<_> = policies
+            .iter()
+            .map(|p| PolicyStats {
+                policy: p.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        for result in &finished {
+            let winner = result.winner_policy();
+            for seat in result.seats {
+                policy_stats[seat].matches += 1;
+            }
+            for (i, seat) in result.seats.into_iter().enumerate() {
+                if result.won(PlayerNumber::ALL[i]) {
+                    policy_stats[seat].wins += 1;
+                } else if winner.is_some() {
+                    policy_stats[seat].losses += 1;
+                }
+            }
+        }
+        for stats in &mut policy_stats {
+            stats.win_rate = rate(stats.wins, stats.matches);
+        }
+
+        let mut matchups: Vec<MatchupStats> = Vec::new();
+        for result in results {
+            let [a, b] = {
+                let [a, b] = result.seats;
+                [a.min(b), a.max(b)]
+            };
+            let (first, second) = (policies[a].to_string(), policies[b].to_string());
+            let i = match matchups
+                .iter()
+                .position(|m| m.first == first && m.second == second)
+            {
+                Some(i) => i,
+                None => {
+                    matchups.push(MatchupStats {
+                        first,
+                        second,
+                        ..Default::default()
+                    });
+                    matchups.len() - 1
+                }
+            };
+            let matchup = &mut matchups[i];
+            matchup.matches += 1;
+            matchup.average_turns += result.turns as f64;
+            match result.winner_policy() {
+                None => matchup.unfinished += 1,
+                Some(winner) if winner == a && a != b => matchup.first_wins += 1,
+                Some(_) if a == b && result.won(PlayerNumber::First) => matchup.first_wins += 1,
+                Some(_) => matchup.second_wins += 1,
+            }
+        }
+        for matchup in &mut matchups {
+            matchup.average_turns /= matchup.matches.max(1) as f64;
+        }
+
+        let mut counters: Vec<CardCounter> = cards.iter().map(|_| CardCounter::default()).collect();
+        let index_of = |id: i32| cards.iter().position(|c| c.id == id);
+        for result in results {
+            for i in 0..2 {
+                for id in &result.used[i] {
+                    if let Some(c) = index_of(*id) {
+                        counters[c].used += 1;
+                    }
+                }
+                for id in &result.dropped[i] {
+                    if let Some(c) = index_of(*id) {
+                        counters[c].discarded += 1;
+                    }
+                }
+                if result.outcome.is_none() {
+                    continue;
+                }
+                let won = result.won(PlayerNumber::ALL[i]);
+                for (card, counter) in cards.iter().zip(counters.iter_mut()) {
+                    if result.used[i].contains(&card.id) {
+                        counter.used_in += 1;
+                        counter.won_used += won as usize;
+                    } else {
+                        counter.not_used_in += 1;
+                        counter.won_not_used += won as usize;
+                    }
+                }
+            }
+        }
+        let cards = cards
+            .iter()
+            .zip(counters)
+            .map(|(card, c)| {
+                let win_rate_when_used = rate(c.won_used, c.used_in);
+                let win_rate_when_not_used = rate(c.won_not_used, c.not_used_in);
+                CardStats {
+                    id: card.id,
+                    name: card.name.clone(),
+                    used: c.used,
+                    discarded: c.discarded,
+                    win_rate_when_used,
+                    win_rate_when_not_used,
+                    win_delta: win_rate_when_used - win_rate_when_not_used,
+                }
+            })
+            .collect();
+
+        Self {
+            seed,
+            rules,
+            matches: results.len(),
+            unfinished: results.len() - finished.len(),
+            average_turns: results.iter().map(|r| r.turns as f64).sum::<f64>()
+                / results.len().max(1) as f64,
+            end_reasons,
+            first_player: FirstPlayer {
+                wins: first_wins,
+                win_rate: rate(first_wins, finished.len()),
+            },
+            policies: policy_stats,
+            matchups,
+            cards,
+        }
+    }
+
+    /// Writes `summary.csv`, `policies.csv`, `matchups.csv` and `cards.csv` into the directory.
+    pub fn write_csv(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let mut summary = String::from(
+            "seed,rules,matches,unfinished,average_turns,tower_destroyed,tower_completed,first_player_wins,first_player_win_rate\n",
+        );
+        let _ = writeln!(
+            summary,
+            "{},{},{},{},{:.2},{},{},{},{:.4}",
+            self.seed,
+            quote(&self.rules),
+            self.matches,
+            self.unfinished,
+            self.average_turns,
+            self.end_reasons.tower_destroyed,
+            self.end_reasons.tower_completed,
+            self.first_player.wins,
+            self.first_player.win_rate
+        );
+        std::fs::write(dir.join("summary.csv"), summary)?;
+
+        let mut policies = String::from("policy,matches,wins,losses,win_rate\n");
+        for p in &self.policies {
+            let _ = writeln!(
+                policies,
+                "{},{},{},{},{:.4}",
+                quote(&p.policy),
+                p.matches,
+                p.wins,
+                p.losses,
+                p.win_rate
+            );
+        }
+        std::fs::write(dir.join("policies.csv"), policies)?;
+
+        let mut matchups =
+            String::from("first,second,matches,first_wins,second_wins,unfinished,average_turns\n");
+        for m in &self.matchups {
+            let _ = writeln!(
+                matchups,
+                "{},{},{},{},{},{},{:.2}",
+                quote(&m.first),
+                quote(&m.second),
+                m.matches,
+                m.first_wins,
+                m.second_wins,
+                m.unfinished,
+                m.average_turns
+            );
+        }
+        std::fs::write(dir.join("matchups.csv"), matchups)?;
+
+        let mut cards = String::from(
+            "id,name,used,discarded,win_rate_when_used,win_rate_when_not_used,win_delta\n",
+        );
+        for c in &self.cards {
+            let _ = writeln!(
+                cards,
+                "{},{},{},{},{:.4},{:.4},{:.4}",
+                c.id,
+                quote(&c.name),
+                c.used,
+                c.discarded,
+                c.win_rate_when_used,
+                c.win_rate_when_not_used,
+                c.win_delta
+            );
+        }
+        std::fs::write(dir.join("cards.csv"), cards)
+    }
+}
+
+fn quote(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
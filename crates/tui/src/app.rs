@@ -0,0 +1,133 @@
+use game_core::ai::AiPolicy;
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::{ActionTaken, MatchEvent, MatchState, RuleError, WinReason};
+use game_core::rng::{self, GameRng, RngStream};
+use std::collections::VecDeque;
+
+const LOG_SIZE: usize = 6;
+
+/// Match against the AI together with the selection in the hand.
+pub struct App {
+    pub state: MatchState,
+    pub me: PlayerNumber,
+    pub selected: usize,
+    pub log: VecDeque<String>,
+    ai: Box<dyn AiPolicy>,
+    rng: GameRng,
+}
+
+impl App {
+    pub fn new(state: MatchState, me: PlayerNumber, ai: Box<dyn AiPolicy>) -> Self {
+        let rng = rng::seeded(state.seed, RngStream::Ai);
+        let mut app = Self {
+            state,
+            me,
+            selected: 0,
+            log: VecDeque::new(),
+            ai,
+            rng,
+        };
+        app.push_log(format!("Match started with seed {}", app.state.seed));
+        app.play_ai();
+        app
+    }
+
+    pub fn select(&mut self, index: usize) {
+        let len = self.state.player(self.me).hand.len();
+        if index < len {
+            self.selected = index;
+        }
+    }
+
+    pub fn select_next(&mut self, offset: isize) {
+        let len = self.state.player(self.me).hand.len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Uses or discards the selected card, then lets the AI move.
+    pub fn act(&mut self, use_card: bool) {
+        if self.state.is_finished() {
+            return;
+        }
+        let Some(card) = self.state.player(self.me).hand.get(self.selected).cloned() else {
+            return;
+        };
+        let action = if use_card {
+            ActionTaken::UseCard { card }
+        } else {
+            ActionTaken::DropCard { card }
+        };
+        match self.state.apply_as(self.me, &action) {
+            Ok(events) => self.log_events(&events),
+            Err(RuleError::CannotAfford {
+                resource,
+                cost,
+                available,
+            }) => self.push_log(format!(
+                "Can't afford it, needs {} {} and you have {}",
+                cost, resource, available
+            )),
+            Err(err) => self.push_log(err.to_string()),
+        }
+        self.play_ai();
+        self.selected = self
+            .selected
+            .min(self.state.player(self.me).hand.len().saturating_sub(1));
+    }
+
+    fn play_ai(&mut self) {
+        while !self.state.is_finished() && self.state.current != self.me {
+            let view = self.state.view(self.state.current);
+            let Some(action) = self.ai.select_action(&view, &mut self.rng) else {
+                // without cards there is nothing to play, the AI gives up
+                let events = self.state.concede(self.state.current).unwrap_or_default();
+                self.log_events(&events);
+                break;
+            };
+            let events = self
+                .state
+                .apply(&action)
+                .expect("AI picked an illegal action");
+            self.log_events(&events);
+        }
+    }
+
+    fn log_events(&mut self, events: &[MatchEvent]) {
+        for event in events {
+            let message = match event {
+                MatchEvent::CardUsed { player, card } => {
+                    format!("{} used {}", self.who(*player), card.name)
+                }
+                MatchEvent::CardDropped { player, card } => {
+                    format!("{} discarded {}", self.who(*player), card.name)
+                }
+                MatchEvent::CardDiscarded { player, card } => {
+                    format!("{} lost {} from the hand", self.who(*player), card.name)
+                }
+                MatchEvent::MatchEnded(outcome) => {
+                    let reason = match outcome.reason {
+                        WinReason::TowerDestroyed => "tower destroyed",
+                        WinReason::TowerCompleted => "tower completed",
+                        WinReason::Conceded => "conceded",
+                    };
+                    format!("{} won, {}", self.who(outcome.winner), reason)
+                }
+                MatchEvent::CardDrawn { .. } | MatchEvent::TurnStarted { .. } => continue,
+            };
+            self.push_log(message);
+        }
+    }
+
+    fn who(&self, player: PlayerNumber) -> &'static str {
+        if player == self.me { "You" } else { "Enemy" }
+    }
+
+    fn push_log(&mut self, message: String) {
+        if self.log.len() == LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.log.push_back(message);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::app::App;
+use crossterm::style::{Color, StyledContent, Stylize};
+use crossterm::{cursor, queue, style, terminal};
+use game_core::data::player::PlayerHealth;
+use game_core::data::resource::ResourceType;
+use game_core::data::ruleset::Ruleset;
+use game_core::data::supply::PlayerSupply;
+use std::io::{self, Write};
+
+const BAR_WIDTH: i32 = 20;
+const KEYS: &str = "←/→ or 1-9 select, Enter/u use, d discard, q quit";
+
+type Line = Vec<StyledContent<String>>;
+
+fn text(value: impl Into<String>) -> StyledContent<String> {
+    value.into().stylize()
+}
+
+fn bar(value: i32, max: i32, color: Color) -> StyledContent<String> {
+    let filled = (value.clamp(0, max) * BAR_WIDTH / max.max(1)) as usize;
+    format!(
+        "[{}{}]",
+        "#".repeat(filled),
+        ".".repeat(BAR_WIDTH as usize - filled)
+    )
+    .with(color)
+}
+
+fn castle(title: &str, health: &PlayerHealth, supply: &PlayerSupply, rules: &Ruleset) -> [Line; 4] {
+    let resources = ResourceType::ALL
+        .into_iter()
+        .map(|res| {
+            let r = supply.get(res);
+            text(format!("  {} {} (+{})", res, r.amount, r.production))
+        })
+        .collect();
+    [
+        vec![text(title).bold()],
+        vec![
+            text(format!(
+                "  Tower {:>3}/{:<3} ",
+                health.tower_hp, rules.max_tower_hp
+            )),
+            bar(health.tower_hp, rules.max_tower_hp, Color::Red),
+        ],
+        vec![
+            text(format!(
+                "  Walls {:>3}/{:<3} ",
+                health.walls_hp, rules.max_walls_hp
+            )),
+            bar(health.walls_hp, rules.max_walls_hp, Color::Grey),
+        ],
+        resources,
+    ]
+}
+
+fn lines(app: &App) -> Vec<Line> {
+    let state = &app.state;
+    let view = state.view(app.me);
+    let mut lines = vec![
+        vec![
+            text("Slavic Castles").bold(),
+            text(format!("  {} rules, turn {}", state.rules.name, state.turn)),
+        ],
+        vec![],
+    ];
+    let enemy_title = format!("Enemy, {} cards in hand", view.enemy_hand_size);
+    lines.extend(castle(
+        &enemy_title,
+        view.enemy_health,
+        view.enemy_supply,
+        view.rules,
+    ));
+    lines.push(vec![]);
+    lines.extend(castle("You", view.health, view.supply, view.rules));
+    lines.push(vec![]);
+    lines.push(vec![
+        text("Your hand").bold(),
+        text(format!("  ({})", KEYS)).dim(),
+    ]);
+    for (i, card) in view.hand.iter().enumerate() {
+        let affordable = view.supply.can_afford_card(card);
+        let marker = if i == app.selected { ">" } else { " " };
+        let title = format!(
+            "{} [{}] {} - {} {}",
+            marker,
+            i + 1,
+            card.name,
+            card.cost_amount,
+            card.cost_resource
+        );
+        lines.push(vec![match (i == app.selected, affordable) {
+            (true, true) => text(title).bold().yellow(),
+            (true, false) => text(title).bold().dark_yellow(),
+            (false, true) => text(title),
+            (false, false) => text(title).dim(),
+        }]);
+        for effect in &card.effects {
+            lines.push(vec![text(format!("        {}", effect)).dim()]);
+        }
+    }
+    lines.push(vec![]);
+    for message in &app.log {
+        lines.push(vec![text(message.clone())]);
+    }
+    if let Some(outcome) = state.outcome() {
+        let result = if outcome.winner == app.me {
+            text("You won! Press q to quit.").green().bold()
+        } else {
+            text("You lost. Press q to quit.").red().bold()
+        };
+        lines.push(vec![]);
+        lines.push(vec![result]);
+    }
+    lines
+}
+
+pub fn draw(out: &mut impl Write, app: &App) -> io::Result<()> {
+    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+    for (row, line) in lines(app).into_iter().enumerate() {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+        for part in line {
+            queue!(out, style::PrintStyledContent(part))?;
+        }
+    }
+    out.flush()
+}
@@ -0,0 +1,144 @@
+use app::App;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::{cursor, execute, terminal};
+use game_core::ai::AiDifficulty;
+use game_core::data::card::Card;
+use game_core::data::deck::Deck;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::{Ruleset, RulesetsAsset};
+use game_core::r#match::MatchState;
+use std::io;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod app;
+mod draw;
+
+const HELP: &str = "Usage: tui [--difficulty <name>] [--seed <n>] [--deck <file>] [--rules <name>] [--rules-file <file>] [--second]
+
+Plays a match against the AI in the terminal, Normal difficulty by default.
+--second lets the AI take the first turn.";
+
+struct Args {
+    difficulty: AiDifficulty,
+    seed: u64,
+    deck: String,
+    rules_file: String,
+    rules: Option<String>,
+    me: PlayerNumber,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        difficulty: AiDifficulty::default(),
+        seed: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        deck: "assets/all.deck.json".to_owned(),
+        rules_file: "assets/all.rules.json".to_owned(),
+        rules: None,
+        me: PlayerNumber::First,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--difficulty" => {
+                let name = value()?;
+                args.difficulty = AiDifficulty::ALL
+                    .into_iter()
+                    .find(|d| d.to_string().eq_ignore_ascii_case(&name))
+                    .ok_or(format!("Unknown difficulty `{}`", name))?;
+            }
+            "--seed" => {
+                let seed = value()?;
+                args.seed = seed
+                    .parse()
+                    .map_err(|_| format!("Invalid seed: {}", seed))?;
+            }
+            "--deck" => args.deck = value()?,
+            "--rules-file" => args.rules_file = value()?,
+            "--rules" => args.rules = Some(value()?),
+            "--second" => args.me = PlayerNumber::Second,
+            _ => return Err(HELP.to_owned()),
+        }
+    }
+    Ok(args)
+}
+
+fn load_match(args: &Args) -> Result<MatchState, String> {
+    let read = |path: &str| {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    };
+    let cards: Vec<Card> =
+        serde_json::from_str(&read(&args.deck)?).map_err(|e| format!("Invalid deck: {}", e))?;
+    let rules = match &args.rules {
+        None => Ruleset::default(),
+        Some(name) => serde_json::from_str::<RulesetsAsset>(&read(&args.rules_file)?)
+            .map_err(|e| format!("Invalid rulesets: {}", e))?
+            .0
+            .into_iter()
+            .find(|r| r.name.eq_ignore_ascii_case(name))
+            .ok_or(format!("No ruleset named `{}`", name))?,
+    };
+    let deck = Deck::from(cards);
+    Ok(MatchState::new([deck.clone(), deck], args.seed, rules))
+}
+
+/// Puts the terminal back to normal even when the match loop fails.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn run(app: &mut App) -> io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut out = io::stdout();
+    loop {
+        draw::draw(&mut out, app)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Left | KeyCode::Up => app.select_next(-1),
+            KeyCode::Right | KeyCode::Down | KeyCode::Tab => app.select_next(1),
+            KeyCode::Char(c @ '1'..='9') => app.select(c as usize - '1' as usize),
+            KeyCode::Enter | KeyCode::Char('u') => app.act(true),
+            KeyCode::Char('d') => app.act(false),
+            _ => {}
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let (args, state) = match parse_args().and_then(|args| load_match(&args).map(|s| (args, s))) {
+        Ok(loaded) => loaded,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut app = App::new(state, args.me, args.difficulty.policy());
+    if let Err(err) = run(&mut app) {
+        eprintln!("Terminal error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    println!("Seed of the match: {}", app.state.seed);
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,146 @@
+use game_core::data::card::Card;
+use game_core::validate::{self, Severity};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const HELP: &str = "Usage: utils_deck_validator [--assets <dir>] [--skip-images] [--deny-warnings] [DECK.deck.json...]
+
+Checks every *.deck.json in the assets directory when no deck is given,
+and that all.avatars.json lists exactly the pictures in avatars/.";
+
+struct Args {
+    assets: PathBuf,
+    skip_images: bool,
+    deny_warnings: bool,
+    decks: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        assets: PathBuf::from("assets"),
+        skip_images: false,
+        deny_warnings: false,
+        decks: Vec::new(),
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--assets" => {
+                args.assets = iter.next().ok_or("--assets needs a directory")?.into();
+            }
+            "--skip-images" => args.skip_images = true,
+            "--deny-warnings" => args.deny_warnings = true,
+            "-h" | "--help" => return Err(HELP.to_owned()),
+            _ => args.decks.push(arg.into()),
+        }
+    }
+    if args.decks.is_empty() {
+        let pattern = args.assets.join("*.deck.json");
+        args.decks = glob::glob(&pattern.to_string_lossy())
+            .map_err(|e| e.to_string())?
+            .filter_map(Result::ok)
+            .collect();
+    }
+    Ok(args)
+}
+
+fn load_deck(path: &Path) -> Result<Vec<Card>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Compares the avatars manifest with the pictures in the avatars directory.
+fn check_avatars(assets: &Path) -> Result<Vec<String>, String> {
+    let content =
+        std::fs::read_to_string(assets.join("all.avatars.json")).map_err(|e| e.to_string())?;
+    let ids: Vec<i32> = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let mut issues = Vec::new();
+    if ids.is_empty() {
+        issues.push("no avatars listed".to_owned());
+    }
+    for id in ids.iter() {
+        if !assets.join(format!("avatars/{}.png", id)).exists() {
+            issues.push(format!("avatar {} has no avatars/{}.png", id, id));
+        }
+    }
+    let pattern = assets.join("avatars").join("*.png");
+    for path in glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+    {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        match name.parse::<i32>() {
+            Ok(id) if ids.contains(&id) => {}
+            Ok(id) => issues.push(format!("avatars/{}.png is not listed", id)),
+            Err(_) => issues.push(format!("avatars/{}.png is not named by an id", name)),
+        }
+    }
+    Ok(issues)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.decks.is_empty() {
+        eprintln!("No decks found in {}", args.assets.display());
+        return ExitCode::FAILURE;
+    }
+
+    let (mut errors, mut warnings) = (0, 0);
+    for deck in args.decks.iter() {
+        let cards = match load_deck(deck) {
+            Ok(cards) => cards,
+            Err(e) => {
+                println!("error: {}: {}", deck.display(), e);
+                errors += 1;
+                continue;
+            }
+        };
+        let mut issues = validate::validate_deck(&cards);
+        if !args.skip_images {
+            issues.extend(validate::find_missing_images(&cards, |path| {
+                args.assets.join(path).exists()
+            }));
+        }
+        issues.sort_by_key(|issue| issue.card_id());
+        for issue in issues.iter() {
+            let label = match issue.severity() {
+                Severity::Error => {
+                    errors += 1;
+                    "error"
+                }
+                Severity::Warning => {
+                    warnings += 1;
+                    "warning"
+                }
+            };
+            println!("{}: {}: {}", label, deck.display(), issue);
+        }
+        println!("Checked {} cards in {}", cards.len(), deck.display());
+    }
+    let manifest = args.assets.join("all.avatars.json");
+    match check_avatars(&args.assets) {
+        Ok(issues) => {
+            for issue in issues.iter() {
+                println!("error: {}: {}", manifest.display(), issue);
+            }
+            errors += issues.len();
+        }
+        Err(e) => {
+            println!("error: {}: {}", manifest.display(), e);
+            errors += 1;
+        }
+    }
+    println!("{} errors, {} warnings", errors, warnings);
+
+    if errors > 0 || (args.deny_warnings && warnings > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
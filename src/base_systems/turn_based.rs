@@ -7,6 +7,10 @@ pub struct CurrentActorToken;
 #[derive(Deref, DerefMut, Component, Default, Reflect)]
 pub struct ActorTurn(pub usize);
 
+/// Actor keeps the token for one more turn.
+#[derive(Default, Debug, Reflect, Component)]
+pub struct ExtraTurn;
+
 #[derive(Debug, Hash, PartialEq, Eq, Default, Clone, SubStates)]
 #[source(GameState = GameState::Game)]
 pub enum GameTurnSteps {
@@ -19,6 +23,7 @@ pub enum GameTurnSteps {
 pub fn register_system(app: &mut App) {
     app.register_type::<ActorTurn>()
         .register_type::<CurrentActorToken>()
+        .register_type::<ExtraTurn>()
         .add_sub_state::<GameTurnSteps>()
         .add_systems(OnExit(GameTurnSteps::PerformAction), remove_token)
         .add_systems(
@@ -59,14 +64,16 @@ pub fn search_for_actors(
 
 fn remove_token(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut ActorTurn), With<CurrentActorToken>>,
+    mut query: Query<(Entity, &mut ActorTurn, Has<ExtraTurn>), With<CurrentActorToken>>,
 ) {
     warn!("Remove token");
-    let Ok((entity, mut delay)) = query.single_mut() else {
+    let Ok((entity, mut delay, extra_turn)) = query.single_mut() else {
         return;
     };
-    delay.0 = **delay + 2;
+    if !extra_turn {
+        delay.0 = **delay + 2;
+    }
     if let Ok(mut e) = commands.get_entity(entity) {
-        e.remove::<CurrentActorToken>();
+        e.remove::<(CurrentActorToken, ExtraTurn)>();
     }
 }
@@ -3,8 +3,10 @@ use crate::components::ObserverExtension;
 use crate::data::deck::HandQueryRead;
 use crate::states::game::{self, ActionTaken, GameObject, HelpDisplay, HumanPlayer};
 use crate::states::game_states::GameState;
+use crate::states::replays::ReplayActor;
 use crate::visual::window_changed_or_component_added;
 use bevy::color::palettes::tailwind;
+use bevy::ecs::query::QueryFilter;
 use bevy::ecs::system::SystemParam;
 use bevy::{prelude::*, reflect::Reflect};
 use game_core::data::card::Card;
@@ -85,6 +87,13 @@ pub enum CardDropZone {
     Discard,
 }
 
+/// Hand of the current actor is displayed when it is played by a human or a replay.
+#[derive(QueryFilter)]
+pub struct DisplayedHandFilter {
+    _a: With<CurrentActorToken>,
+    _b: Or<(With<HumanPlayer>, With<ReplayActor>)>,
+}
+
 pub struct CardPlugin;
 
 impl Plugin for CardPlugin {
@@ -232,7 +241,7 @@ fn add_cards(
     windows: Query<&Window>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    deck_q: Query<HandQueryRead, (With<HumanPlayer>, With<CurrentActorToken>)>,
+    deck_q: Query<HandQueryRead, DisplayedHandFilter>,
     places: CardPlaces,
 ) -> Result {
     let Ok(hand) = deck_q.single() else {
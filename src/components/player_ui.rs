@@ -17,6 +17,8 @@ pub enum PlayerTextInterface {
     Health,
     #[default]
     Shield,
+    DrawPile,
+    DiscardPile,
 }
 
 trait PlayerInterfaceHelper {
@@ -98,6 +100,8 @@ impl TextUiPlayerElementsItem<'_> {
             }
             PlayerTextInterface::Health => data.player.tower_hp,
             PlayerTextInterface::Shield => data.player.walls_hp,
+            PlayerTextInterface::DrawPile => data.piles.draw.len() as i32,
+            PlayerTextInterface::DiscardPile => data.piles.discard.len() as i32,
         };
         let result = self.value.update(new_value);
         if result == UpdateResult::NoChange {
@@ -117,14 +121,15 @@ pub struct PlayerUiPlugin;
 
 impl Plugin for PlayerUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (update_created_player_ui, update_player_ui).run_if(in_state(GameState::Game)),
-        )
-        .add_systems(
-            OnEnter(GameState::Game),
-            (setup_player_ui).after(game::init_players),
-        );
+        app.register_type::<PlayerPanel>()
+            .add_systems(
+                Update,
+                (update_created_player_ui, update_player_ui).run_if(in_state(GameState::Game)),
+            )
+            .add_systems(
+                OnEnter(GameState::Game),
+                (setup_player_ui).after(game::init_players),
+            );
     }
 }
 
@@ -132,6 +137,29 @@ impl Plugin for PlayerUiPlugin {
 #[require(AnimationPlayer)]
 pub struct PlayerUi(pub PlayerNumber);
 
+/// Root node of all the information displayed about the player.
+#[derive(Component, Reflect, Deref, Default, Debug)]
+pub struct PlayerPanel(pub PlayerNumber);
+
+/// Panel placed on the left side of the screen, or the right one when `right_align` is set.
+pub fn panel_node(right_align: bool) -> Node {
+    let (left, right, align_items) = if right_align {
+        (Val::Auto, Val::Px(15.0), AlignItems::FlexEnd)
+    } else {
+        (Val::Px(15.0), Val::Auto, AlignItems::FlexStart)
+    };
+    Node {
+        position_type: PositionType::Absolute,
+        left,
+        right,
+        margin: UiRect::all(Val::Px(5.0)),
+        padding: UiRect::all(Val::Px(15.0)),
+        flex_direction: FlexDirection::Column,
+        align_items,
+        ..default()
+    }
+}
+
 fn update_created_player_ui(
     mut ui_query: Query<TextUiPlayerElements, Added<PlayerTextInterface>>,
     player_query: Players,
@@ -190,38 +218,11 @@ fn setup_player_ui(mut commands: Commands, asset_server: Res<AssetServer>, playe
     let header_style =
         TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(30.0);
 
-    for (player, style, right_align) in [
-        (
-            PlayerNumber::First,
-            Node {
-                position_type: PositionType::Absolute,
-                left: Val::Px(15.0),
-                margin: UiRect::all(Val::Px(5.0)),
-                padding: UiRect::all(Val::Px(15.0)),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::FlexStart,
-                ..default()
-            },
-            false,
-        ),
-        (
-            PlayerNumber::Second,
-            Node {
-                position_type: PositionType::Absolute,
-                right: Val::Px(15.0),
-                margin: UiRect::all(Val::Px(5.0)),
-                padding: UiRect::all(Val::Px(15.0)),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::FlexEnd,
-                ..default()
-            },
-            true,
-        ),
-    ] {
+    for (player, right_align) in [(PlayerNumber::First, false), (PlayerNumber::Second, true)] {
         let player_info = player_query.get_player(player).expect("ERROR");
         let avatar_path = player_info.details.avatar_path();
         commands
-            .spawn((style.clone(), GameObject))
+            .spawn((panel_node(right_align), PlayerPanel(player), GameObject))
             .insert(Name::new(format!("Ui{:?}", player)))
             .with_children(|p| {
                 p.spawn((
@@ -280,6 +281,20 @@ fn setup_player_ui(mut commands: Commands, asset_server: Res<AssetServer>, playe
                     res.spawn(ImageNode::new(asset_server.load("img/player_shield.png")));
                     res.spawn(asset_server.player_ui(PlayerTextInterface::Shield, player));
                 });
+                p.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(5.0),
+                    margin: UiRect::bottom(Val::Px(5.0)),
+                    ..default()
+                })
+                .with_children(|piles| {
+                    let label_font = TextFont::from_font(asset_server.load(consts::REGULAR_FONT))
+                        .with_font_size(16.0);
+                    piles.spawn((Text::new("Draw"), label_font.clone()));
+                    piles.spawn(asset_server.player_ui(PlayerTextInterface::DrawPile, player));
+                    piles.spawn((Text::new("Discard"), label_font));
+                    piles.spawn(asset_server.player_ui(PlayerTextInterface::DiscardPile, player));
+                });
                 for (resource, gfx) in [
                     (ResourceType::Magic, "potionBlue"),
                     (ResourceType::Tools, "tools"),
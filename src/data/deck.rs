@@ -1,17 +1,16 @@
+use crate::data::profile::ProfileProvider;
 use bevy::ecs::query::QueryData;
 use bevy::prelude::*;
 use bevy::reflect::Reflect;
-use game_core::consts::BASE_RESOURCE_AMOUNT;
-use game_core::consts::CARDS_IN_DECK;
+use bevy_pkv::PkvStore;
+use game_core::consts;
 use game_core::data::card::Card;
+pub use game_core::data::deck::DeckEntry;
+pub use game_core::data::hand::HandCards;
 use game_core::data::supply::PlayerSupply;
-use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::str;
 
-#[derive(Serialize, Deserialize, Reflect, Component, Deref, DerefMut, Default, Debug)]
-pub struct HandCards(pub Vec<Card>);
-
 #[derive(QueryData)]
 #[query_data(derive(Debug))]
 pub struct HandQueryRead {
@@ -35,12 +34,12 @@ impl CardInfo<'_> {
     }
 
     pub fn image_path(&self) -> String {
-        format!("cards/{}.png", self.card.id)
+        self.card.get_image_asset()
     }
 }
 
 impl HandQueryReadItem<'_> {
-    pub fn card_info_array(&self) -> Vec<CardInfo> {
+    pub fn card_info_array(&self) -> Vec<CardInfo<'_>> {
         self.cards
             .iter()
             .enumerate()
@@ -57,60 +56,124 @@ impl HandQueryReadItem<'_> {
 }
 
 #[derive(
-    serde::Deserialize, bevy::asset::Asset, Deref, Debug, DerefMut, Reflect, Default, Clone,
+    serde::Deserialize,
+    serde::Serialize,
+    bevy::asset::Asset,
+    Deref,
+    Debug,
+    DerefMut,
+    Reflect,
+    Default,
+    Clone,
 )]
 pub struct DeckAsset(pub Vec<Card>);
 
-impl HandCards {
-    pub fn replace_card(&mut self, card_nr: usize, resources: &PlayerSupply, deck: &[Card]) {
-        let mut rng = thread_rng();
-        let mut finded = false;
-
-        let mut card = &deck[0];
-        for attempt in 0..9 {
-            let i: usize = rng.r#gen::<usize>() % deck.len();
-            card = &deck[i];
-            let max_cost_amount = resources.get(card.cost_resource).amount + 2;
-            let card_already_in_deck = attempt > 5 || self.iter().any(|c| c.id == card.id);
-            if card.cost_amount <= max_cost_amount && !card_already_in_deck {
-                self[card_nr] = card.clone();
-                finded = true;
-                break;
-            }
+/// Size and copy limits for the decks built by players.
+#[derive(Resource, Reflect, Debug, Clone, Copy)]
+#[reflect(Resource)]
+pub struct DeckLimits {
+    pub min_size: u32,
+    pub max_size: u32,
+    pub max_copies: u32,
+}
+
+impl Default for DeckLimits {
+    fn default() -> Self {
+        Self {
+            min_size: consts::MIN_DECK_SIZE,
+            max_size: consts::MAX_DECK_SIZE,
+            max_copies: consts::MAX_CARD_COPIES,
         }
-        if !finded {
-            self[card_nr] = card.clone();
+    }
+}
+
+/// Deck built by the player, cards are referenced by the id from [`DeckAsset`].
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CustomDeck {
+    pub entries: Vec<DeckEntry>,
+}
+
+impl CustomDeck {
+    /// Deck with every card of the asset, using its default copies.
+    pub fn from_asset(asset: &DeckAsset) -> Self {
+        Self {
+            entries: asset
+                .iter()
+                .map(|card| DeckEntry {
+                    id: card.id,
+                    copies: card.copies,
+                })
+                .collect(),
         }
     }
 
-    pub fn rnd(&self) -> usize {
-        let mut rng = thread_rng();
-        let i: usize = rng.r#gen::<usize>() % self.len();
-        i
+    pub fn size(&self) -> u32 {
+        self.entries.iter().map(|e| e.copies).sum()
     }
 
-    pub fn generate_random(cards: &[Card]) -> Self {
-        let mut deck = HandCards::default();
-        let mut rng = thread_rng();
-        let max_cost_amount = BASE_RESOURCE_AMOUNT * 120 / 100;
-        for _x in 0..CARDS_IN_DECK {
-            let mut found = false;
-
-            let mut card: &Card = &cards[0];
-            for attempt in 0..9 {
-                let i: usize = rng.r#gen::<usize>() % cards.len();
-                card = &cards[i];
-                let card_already_in_deck = attempt > 5 || deck.iter().any(|c| c.id == card.id);
-                if card.cost_amount <= max_cost_amount && !card_already_in_deck {
-                    deck.push(card.clone());
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                deck.push(card.clone());
-            }
+    pub fn copies(&self, id: i32) -> u32 {
+        self.entries
+            .iter()
+            .find(|e| e.id == id)
+            .map_or(0, |e| e.copies)
+    }
+
+    /// Returns false if it would break the limits.
+    pub fn add(&mut self, id: i32, limits: &DeckLimits) -> bool {
+        if self.size() >= limits.max_size || self.copies(id) >= limits.max_copies {
+            return false;
+        }
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => entry.copies += 1,
+            None => self.entries.push(DeckEntry { id, copies: 1 }),
+        }
+        true
+    }
+
+    pub fn remove(&mut self, id: i32) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            entry.copies = entry.copies.saturating_sub(1);
+        }
+        self.entries.retain(|e| e.copies > 0);
+    }
+
+    pub fn is_valid(&self, limits: &DeckLimits) -> bool {
+        let size = self.size();
+        size >= limits.min_size
+            && size <= limits.max_size
+            && self.entries.iter().all(|e| e.copies <= limits.max_copies)
+    }
+
+    /// Cards from the asset with copies taken from the deck, unknown ids are skipped.
+    pub fn build(&self, asset: &DeckAsset) -> DeckAsset {
+        DeckAsset(
+            self.entries
+                .iter()
+                .filter_map(|entry| {
+                    let card = asset.iter().find(|c| c.id == entry.id)?;
+                    Some(Card {
+                        copies: entry.copies,
+                        ..card.clone()
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Stores the deck in the selected profile.
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if !pkv.update_profile(|profile| profile.deck = Some(self.clone())) {
+            warn!("No profile selected, the deck is not saved");
         }
-        deck
+    }
+}
+
+pub trait CustomDeckProvider {
+    fn get_custom_deck(&self) -> Option<CustomDeck>;
+}
+
+impl CustomDeckProvider for PkvStore {
+    fn get_custom_deck(&self) -> Option<CustomDeck> {
+        self.get_profile()?.deck
     }
 }
@@ -0,0 +1,128 @@
+use game_core::data::card::Card;
+use game_core::data::resource::ResourceType;
+use game_core::r#match::WinReason;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Older matches are dropped from the history of the profile.
+pub const MAX_STORED_MATCHES: usize = 50;
+
+/// Card used or discarded by the profile player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayedCard {
+    pub id: i32,
+    pub name: String,
+    pub cost_resource: ResourceType,
+    pub cost_amount: i32,
+}
+
+impl From<&Card> for PlayedCard {
+    fn from(card: &Card) -> Self {
+        Self {
+            id: card.id,
+            name: card.name.clone(),
+            cost_resource: card.cost_resource,
+            cost_amount: card.cost_amount,
+        }
+    }
+}
+
+/// Finished match, seen from the perspective of the profile player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub opponent_name: String,
+    pub opponent_avatar_id: i32,
+    pub won: bool,
+    pub reason: WinReason,
+    pub turns: u32,
+    pub cards_used: Vec<PlayedCard>,
+    pub cards_discarded: Vec<PlayedCard>,
+    /// Sum of the turn times, in seconds
+    pub duration: f32,
+}
+
+impl MatchRecord {
+    pub fn reason_label(&self) -> &'static str {
+        match self.reason {
+            WinReason::TowerDestroyed => "tower destroyed",
+            WinReason::TowerCompleted => "tower completed",
+            WinReason::Conceded => "conceded",
+        }
+    }
+}
+
+/// Cards of one resource used in the recorded matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub resource: ResourceType,
+    pub cards: u32,
+    pub spent: i32,
+}
+
+/// Statistics calculated from the match history, the most recent match first.
+#[derive(Debug, Clone, Default)]
+pub struct HistorySummary {
+    /// Positive for wins in a row, negative for losses in a row
+    pub current_streak: i32,
+    pub best_win_streak: u32,
+    /// Names of the most used cards with the use count
+    pub favourite_cards: Vec<(String, u32)>,
+    pub resources: Vec<ResourceUsage>,
+    pub discarded: usize,
+}
+
+impl HistorySummary {
+    pub fn new(history: &[MatchRecord], favourites: usize) -> Self {
+        let current_streak = match history.first() {
+            Some(first) => {
+                let len = history.iter().take_while(|m| m.won == first.won).count() as i32;
+                if first.won { len } else { -len }
+            }
+            None => 0,
+        };
+        let mut best_win_streak = 0;
+        let mut streak = 0;
+        for record in history.iter().rev() {
+            streak = if record.won { streak + 1 } else { 0 };
+            best_win_streak = best_win_streak.max(streak);
+        }
+
+        let mut uses: HashMap<&str, u32> = HashMap::new();
+        for card in history.iter().flat_map(|m| &m.cards_used) {
+            *uses.entry(card.name.as_str()).or_default() += 1;
+        }
+        let mut favourite_cards: Vec<(String, u32)> = uses
+            .into_iter()
+            .map(|(name, count)| (name.to_owned(), count))
+            .collect();
+        favourite_cards.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        favourite_cards.truncate(favourites);
+
+        let resources = ResourceType::ALL
+            .into_iter()
+            .map(|resource| {
+                let mut usage = ResourceUsage {
+                    resource,
+                    ..Default::default()
+                };
+                for card in history
+                    .iter()
+                    .flat_map(|m| &m.cards_used)
+                    .filter(|c| c.cost_resource == resource)
+                {
+                    usage.cards += 1;
+                    usage.spent += card.cost_amount;
+                }
+                usage
+            })
+            .collect();
+
+        Self {
+            current_streak,
+            best_win_streak,
+            favourite_cards,
+            resources,
+            discarded: history.iter().map(|m| m.cards_discarded.len()).sum(),
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod deck;
+pub mod match_history;
 pub mod profile;
+pub mod replay;
+pub mod saved_match;
+pub mod seed;
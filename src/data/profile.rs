@@ -1,11 +1,57 @@
-use bevy::{prelude::Component, reflect::Reflect};
+use crate::data::deck::CustomDeck;
+use crate::data::match_history::{MAX_STORED_MATCHES, MatchRecord};
+use bevy::{
+    asset::Asset,
+    prelude::{Component, Deref, Resource},
+    reflect::Reflect,
+};
 use bevy_pkv::PkvStore;
+use rand::Rng;
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 
+const PROFILES_KEY: &str = "profiles";
+/// Keys used before there could be more than one profile
+const LEGACY_PROFILE_KEY: &str = "profile_info";
+const LEGACY_DECK_KEY: &str = "deck_info";
+
+#[derive(Reflect, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileStats {
+    pub matches_won: u32,
+    pub matches_lost: u32,
+}
+
+impl ProfileStats {
+    pub fn record(&mut self, won: bool) {
+        if won {
+            self.matches_won += 1;
+        } else {
+            self.matches_lost += 1;
+        }
+    }
+
+    /// Percent of the matches won, `None` before the first match.
+    pub fn win_rate(&self) -> Option<f32> {
+        let matches = self.matches_won + self.matches_lost;
+        (matches > 0).then(|| self.matches_won as f32 * 100.0 / matches as f32)
+    }
+}
+
 #[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
+    #[serde(default)]
+    pub id: u32,
     pub avatar_id: i32,
     pub name: String,
+    /// Deck built by the player, the default deck is used without it
+    #[serde(default)]
+    pub deck: Option<CustomDeck>,
+    #[serde(default)]
+    pub stats: ProfileStats,
+    /// Finished matches, the most recent first
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub history: Vec<MatchRecord>,
 }
 
 impl Profile {
@@ -13,9 +59,11 @@ impl Profile {
         Self::format_avatar_path(self.avatar_id)
     }
 
-    pub fn save_profile(self, pkv: &mut PkvStore) {
-        pkv.set("profile_info", &self)
-            .expect("Failed to store profile");
+    /// Adds the match to the history and the stats.
+    pub fn record_match(&mut self, record: MatchRecord) {
+        self.stats.record(record.won);
+        self.history.insert(0, record);
+        self.history.truncate(MAX_STORED_MATCHES);
     }
 
     pub fn format_avatar_path(id: i32) -> String {
@@ -23,24 +71,139 @@ impl Profile {
     }
 }
 
+/// Every local profile, the selected one is used for the matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    pub list: Vec<Profile>,
+    pub selected: Option<u32>,
+    next_id: u32,
+}
+
+impl Profiles {
+    pub fn get(&self, id: u32) -> Option<&Profile> {
+        self.list.iter().find(|p| p.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Profile> {
+        self.list.iter_mut().find(|p| p.id == id)
+    }
+
+    pub fn selected(&self) -> Option<&Profile> {
+        self.get(self.selected?)
+    }
+
+    /// Adds the profile with a new id and returns that id.
+    pub fn add(&mut self, mut profile: Profile) -> u32 {
+        let id = self
+            .next_id
+            .max(self.list.iter().map(|p| p.id + 1).max().unwrap_or(0));
+        self.next_id = id + 1;
+        profile.id = id;
+        self.list.push(profile);
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.list.retain(|p| p.id != id);
+        if self.selected == Some(id) {
+            self.selected = None;
+        }
+    }
+}
+
+/// Profile picked in the profile selection screen, the menu asks
+/// for one until it is picked in the current session.
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct ActiveProfile(pub u32);
+
 pub trait ProfileProvider {
-    fn get_profile(&self) -> Option<Profile>;
+    fn get_profiles(&self) -> Profiles;
+    fn save_profiles(&mut self, profiles: &Profiles);
+    /// Selected profile
+    fn get_profile(&self) -> Option<Profile> {
+        self.get_profiles().selected().cloned()
+    }
     fn has_profile(&self) -> bool {
         self.get_profile().is_some()
     }
+    /// Changes the selected profile and stores it, returns `false` when none is selected.
+    fn update_profile(&mut self, f: impl FnOnce(&mut Profile)) -> bool {
+        let mut profiles = self.get_profiles();
+        let Some(profile) = profiles.selected.and_then(|id| profiles.get_mut(id)) else {
+            return false;
+        };
+        f(profile);
+        self.save_profiles(&profiles);
+        true
+    }
+    /// Moves the profile and the deck stored by older versions into the profiles list.
+    fn migrate_profiles(&mut self);
 }
 
 impl ProfileProvider for PkvStore {
-    fn get_profile(&self) -> Option<Profile> {
-        self.get::<Profile>("profile_info").ok()
+    fn get_profiles(&self) -> Profiles {
+        self.get::<Profiles>(PROFILES_KEY).unwrap_or_default()
+    }
+
+    fn save_profiles(&mut self, profiles: &Profiles) {
+        self.set(PROFILES_KEY, profiles)
+            .expect("Failed to store profiles");
+    }
+
+    fn migrate_profiles(&mut self) {
+        let Ok(mut legacy) = self.get::<Profile>(LEGACY_PROFILE_KEY) else {
+            return;
+        };
+        legacy.deck = self.get::<CustomDeck>(LEGACY_DECK_KEY).ok();
+        let mut profiles = self.get_profiles();
+        let id = profiles.add(legacy);
+        profiles.selected.get_or_insert(id);
+        self.save_profiles(&profiles);
+        let _ = self.remove(LEGACY_PROFILE_KEY);
+        let _ = self.remove(LEGACY_DECK_KEY);
     }
 }
 
 impl Default for Profile {
     fn default() -> Self {
         Self {
+            id: 0,
             avatar_id: 1,
             name: "Wojmir".to_owned(),
+            deck: None,
+            stats: ProfileStats::default(),
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Ids of the avatars in `assets/avatars`, loaded from `all.avatars.json`.
+#[derive(Deserialize, Asset, Deref, Reflect, Debug)]
+pub struct AvatarsAsset(pub Vec<i32>);
+
+impl AvatarsAsset {
+    /// Falls back to the default avatar when none are listed.
+    pub fn random(&self, rng: &mut impl Rng) -> i32 {
+        self.0
+            .choose(rng)
+            .copied()
+            .unwrap_or(Profile::default().avatar_id)
+    }
+
+    pub fn next(&self, id: i32) -> i32 {
+        self.offset(id, 1)
+    }
+
+    pub fn previous(&self, id: i32) -> i32 {
+        self.offset(id, -1)
+    }
+
+    /// Keeps the `id` when no avatars are listed.
+    fn offset(&self, id: i32, offset: isize) -> i32 {
+        if self.0.is_empty() {
+            return id;
         }
+        let i = self.0.iter().position(|a| *a == id).unwrap_or(0) as isize;
+        self.0[(i + offset).rem_euclid(self.0.len() as isize) as usize]
     }
 }
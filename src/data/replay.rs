@@ -0,0 +1,98 @@
+use crate::states::game::PlayerInformation;
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
+use game_core::r#match::{ActionTaken, MatchOutcome, WinReason};
+use serde::{Deserialize, Serialize};
+
+/// Bump when the replay format changes in a way older builds can't read.
+pub const REPLAY_VERSION: u32 = 3;
+const MAX_STORED_REPLAYS: usize = 10;
+
+/// Everything needed to play a match again: the seed drives all card draws,
+/// so only the actions picked by players have to be stored.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub player: PlayerInformation,
+    pub opponent: PlayerInformation,
+    #[serde(default)]
+    pub rules: Ruleset,
+    pub actions: Vec<ActionTaken>,
+    /// Player that gave up the match after the last action
+    #[serde(default)]
+    pub conceded: Option<PlayerNumber>,
+    pub outcome: Option<MatchOutcome>,
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        player: &PlayerInformation,
+        opponent: &PlayerInformation,
+        rules: &Ruleset,
+    ) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            player: player.clone(),
+            opponent: opponent.clone(),
+            rules: rules.clone(),
+            actions: vec![],
+            conceded: None,
+            outcome: None,
+        }
+    }
+
+    /// Actions together with the concession, if the match ended with one.
+    pub fn steps(&self) -> usize {
+        self.actions.len() + usize::from(self.conceded.is_some())
+    }
+
+    pub fn finish(&mut self, outcome: MatchOutcome) {
+        if outcome.reason == WinReason::Conceded {
+            self.conceded = Some(outcome.winner.other());
+        }
+        self.outcome = Some(outcome);
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Returns `None` for broken replays or ones saved in other format version.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let replay: Self = serde_json::from_str(json).ok()?;
+        (replay.version == REPLAY_VERSION).then_some(replay)
+    }
+}
+
+pub trait ReplayProvider {
+    /// Stored replays, the most recent first.
+    fn get_replays(&self) -> Vec<Replay>;
+    fn save_replay(&mut self, replay: &Replay);
+}
+
+impl ReplayProvider for PkvStore {
+    fn get_replays(&self) -> Vec<Replay> {
+        self.get::<Vec<String>>("replays")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|json| Replay::from_json(json))
+            .collect()
+    }
+
+    fn save_replay(&mut self, replay: &Replay) {
+        let Ok(json) = replay.to_json() else {
+            error!("Failed to serialize replay");
+            return;
+        };
+        let mut replays = self.get::<Vec<String>>("replays").unwrap_or_default();
+        replays.insert(0, json);
+        replays.truncate(MAX_STORED_REPLAYS);
+        self.set("replays", &replays)
+            .expect("Failed to store replays");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::data::profile::ProfileProvider;
+use crate::data::replay::Replay;
+use crate::states::game::{GameMode, PlayerDetailsInfo, PlayerInformation};
+use crate::states::statistics::MatchTracker;
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::ai::AiDifficulty;
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::MatchState;
+use game_core::rng::GameRng;
+use serde::{Deserialize, Serialize};
+
+/// Bump when the format changes in a way older builds can't read.
+pub const SAVED_MATCH_VERSION: u32 = 1;
+
+/// Every profile has its own saved match.
+fn saved_match_key(profile_id: u32) -> String {
+    format!("saved_match_{}", profile_id)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub nr: PlayerNumber,
+    pub details: PlayerDetailsInfo,
+    /// Value of the `ActorTurn` of the player
+    pub turn: usize,
+}
+
+/// Match interrupted before it ended. The match state holds the health, supply,
+/// hands and piles of both players together with the state of its rng.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedMatch {
+    pub version: u32,
+    pub state: MatchState,
+    pub players: Vec<SavedPlayer>,
+    pub mode: GameMode,
+    pub difficulty: AiDifficulty,
+    pub player: PlayerInformation,
+    pub opponent: PlayerInformation,
+    /// State of the rng used by the AI
+    pub ai_rng: GameRng,
+    pub replay: Option<Replay>,
+    /// Cards and turn times counted so far for the profile statistics
+    #[serde(default)]
+    pub tracker: Option<MatchTracker>,
+}
+
+/// Saved match that is being restored, present until the match is left.
+#[derive(Resource, Debug, Deref)]
+pub struct ResumedMatch(pub SavedMatch);
+
+impl ResumedMatch {
+    pub fn player(&self, nr: PlayerNumber) -> Option<&SavedPlayer> {
+        self.players.iter().find(|p| p.nr == nr)
+    }
+}
+
+/// Saved matches of the selected profile.
+pub trait SavedMatchProvider {
+    /// Returns `None` when there is no match or it was saved in other format version.
+    fn get_saved_match(&self) -> Option<SavedMatch>;
+    fn save_match(&mut self, saved: &SavedMatch);
+    fn clear_saved_match(&mut self);
+    /// Removes the match of the given profile, used when the profile is deleted.
+    fn remove_saved_match(&mut self, profile_id: u32);
+    fn has_saved_match(&self) -> bool {
+        self.get_saved_match().is_some()
+    }
+}
+
+impl SavedMatchProvider for PkvStore {
+    fn get_saved_match(&self) -> Option<SavedMatch> {
+        let key = saved_match_key(self.get_profiles().selected?);
+        let json = self.get::<String>(&key).ok()?;
+        let saved: SavedMatch = serde_json::from_str(&json).ok()?;
+        (saved.version == SAVED_MATCH_VERSION).then_some(saved)
+    }
+
+    fn save_match(&mut self, saved: &SavedMatch) {
+        let Some(id) = self.get_profiles().selected else {
+            warn!("No profile selected, the match is not saved");
+            return;
+        };
+        let Ok(json) = serde_json::to_string(saved) else {
+            error!("Failed to serialize the match");
+            return;
+        };
+        self.set(saved_match_key(id), &json)
+            .expect("Failed to store the match");
+    }
+
+    fn clear_saved_match(&mut self) {
+        if let Some(id) = self.get_profiles().selected {
+            self.remove_saved_match(id);
+        }
+    }
+
+    fn remove_saved_match(&mut self, profile_id: u32) {
+        let key = saved_match_key(profile_id);
+        if self.get::<String>(&key).is_ok() {
+            self.remove(&key).expect("Failed to remove the saved match");
+        }
+    }
+}
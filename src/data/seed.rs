@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use game_core::rng::{self, GameRng, RngStream};
+use rand::{Rng, thread_rng};
+
+/// Seed used for everything random in the next or current match.
+#[derive(Resource, Reflect, Debug, Clone, Copy)]
+#[reflect(Resource)]
+pub struct MatchSeed {
+    pub seed: u64,
+    /// Seed was set by the user, so every match keeps using it
+    pub fixed: bool,
+}
+
+impl Default for MatchSeed {
+    fn default() -> Self {
+        Self {
+            seed: thread_rng().r#gen(),
+            fixed: false,
+        }
+    }
+}
+
+impl MatchSeed {
+    /// Reads the seed from `--seed <number>` command line argument
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let seed = args
+            .iter()
+            .position(|a| a == "--seed")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|s| s.parse::<u64>().ok());
+        match seed {
+            Some(seed) => Self { seed, fixed: true },
+            None => Self::default(),
+        }
+    }
+
+    /// Picks a new seed for the next match unless it was set by the user
+    pub fn roll(&mut self) {
+        if !self.fixed {
+            self.seed = thread_rng().r#gen();
+        }
+    }
+
+    pub fn rng(&self, stream: RngStream) -> GameRng {
+        rng::seeded(self.seed, stream)
+    }
+}
+
+/// Seeded generator for random decisions made outside of the match rules.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SeededRng(pub GameRng);
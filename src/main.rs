@@ -4,6 +4,9 @@ use bevy_common_assets::json::JsonAssetPlugin;
 // use bevy_ecss::prelude::*;
 use bevy_pkv::PkvStore;
 use data::deck::DeckAsset;
+use data::profile::AvatarsAsset;
+use data::seed::MatchSeed;
+use game_core::data::ruleset::RulesetsAsset;
 use states::game::NamesAsset;
 pub mod base_systems;
 pub mod components;
@@ -41,15 +44,21 @@ pub fn main() {
     app.add_plugins(helpers::plugin)
         .add_plugins(JsonAssetPlugin::<DeckAsset>::new(&["deck.json"]))
         .add_plugins(JsonAssetPlugin::<NamesAsset>::new(&["names.json"]))
+        .add_plugins(JsonAssetPlugin::<AvatarsAsset>::new(&["avatars.json"]))
+        .add_plugins(JsonAssetPlugin::<RulesetsAsset>::new(&["rules.json"]))
         .add_plugins(base_systems::turn_based::register_system)
         .add_plugins(components::player_ui::PlayerUiPlugin)
         .init_state::<states::game_states::GameState>()
         .add_plugins(helpers::wasm_resize::WindowResizePlugin)
         .register_type::<DeckAsset>()
         .register_type::<NamesAsset>()
+        .register_type::<AvatarsAsset>()
+        .register_type::<RulesetsAsset>()
         .add_plugins(visual::plugin)
         .add_plugins(game_core::GameCorePlugin)
         .insert_resource(PkvStore::new("CoolGames", NAME))
+        .insert_resource(MatchSeed::from_args())
+        .register_type::<MatchSeed>()
         // .add_plugins(EcssPlugin::default())
         .add_plugins(bevy_tweening::TweeningPlugin)
         .add_plugins(states::game_states::GamePlugins)
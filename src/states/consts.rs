@@ -6,8 +6,11 @@ pub const HOVERED_BUTTON: Color = Color::linear_rgb(0.9, 0.9, 0.9);
 pub const PRESSED_BUTTON: Color = Color::linear_rgb(0.5, 0.5, 0.5);
 pub const FULL_SIZE_PERCENT: Val = Val::Percent(100.);
 
-pub const AVATARS_AMOUNT: i32 = 13;
-
-pub const HELP_TEXT: &str = "Get 50 life or destroy opponent to win\n Drag card to center to use it\n or drag it down to discard.\n H- show this info";
+pub fn help_text(max_tower_hp: i32) -> String {
+    format!(
+        "Get {} life or destroy opponent to win\n Drag card to center to use it\n or drag it down to discard.\n H- show this info\n Esc- pause",
+        max_tower_hp
+    )
+}
 pub const LABEL_FONT: &str = "fonts/PirataOne-Regular.ttf";
 pub const REGULAR_FONT: &str = "fonts/AlteHaasGroteskBold.ttf";
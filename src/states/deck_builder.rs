@@ -0,0 +1,422 @@
+use super::consts;
+use super::game_states::GameState;
+use crate::data::deck::{CustomDeck, CustomDeckProvider, DeckAsset, DeckLimits};
+use crate::helpers::button::ButtonReleased;
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::data::card::Card;
+use game_core::data::resource::ResourceType;
+
+pub struct DeckBuilderPlugin;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+enum CostFilter {
+    #[default]
+    Any,
+    Cheap,
+    Medium,
+    Expensive,
+}
+
+impl CostFilter {
+    fn next(self) -> Self {
+        match self {
+            CostFilter::Any => CostFilter::Cheap,
+            CostFilter::Cheap => CostFilter::Medium,
+            CostFilter::Medium => CostFilter::Expensive,
+            CostFilter::Expensive => CostFilter::Any,
+        }
+    }
+
+    fn matches(self, cost: i32) -> bool {
+        match self {
+            CostFilter::Any => true,
+            CostFilter::Cheap => cost <= 3,
+            CostFilter::Medium => (4..=7).contains(&cost),
+            CostFilter::Expensive => cost >= 8,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            CostFilter::Any => "Cost: Any",
+            CostFilter::Cheap => "Cost: 0-3",
+            CostFilter::Medium => "Cost: 4-7",
+            CostFilter::Expensive => "Cost: 8+",
+        }
+    }
+}
+
+/// Deck being edited and the filters of the card browser.
+#[derive(Resource, Debug, Default, Reflect)]
+struct DeckBuilder {
+    deck: CustomDeck,
+    resource: Option<ResourceType>,
+    cost: CostFilter,
+    message: Option<String>,
+}
+
+impl DeckBuilder {
+    fn shows(&self, card: &Card) -> bool {
+        self.resource.is_none_or(|r| r == card.cost_resource) && self.cost.matches(card.cost_amount)
+    }
+
+    fn next_resource(&mut self) {
+        self.resource = match self.resource {
+            None => Some(ResourceType::Tools),
+            Some(ResourceType::Tools) => Some(ResourceType::Magic),
+            Some(ResourceType::Magic) => Some(ResourceType::Soldiers),
+            Some(ResourceType::Soldiers) => None,
+        };
+    }
+
+    fn resource_label(&self) -> String {
+        match self.resource {
+            Some(resource) => format!("Resource: {}", resource),
+            None => "Resource: All".to_owned(),
+        }
+    }
+}
+
+#[derive(Component)]
+enum DeckBuilderButton {
+    Add(i32),
+    Remove(i32),
+    ResourceFilter,
+    CostFilter,
+    Reset,
+    Save,
+    Back,
+}
+
+#[derive(Component)]
+struct CardBrowser;
+
+#[derive(Component)]
+struct DeckList;
+
+#[derive(Component)]
+struct DeckStatus;
+
+#[derive(Component)]
+enum FilterLabel {
+    Resource,
+    Cost,
+}
+
+impl Plugin for DeckBuilderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeckLimits>()
+            .register_type::<DeckLimits>()
+            .add_systems(
+                OnEnter(GameState::DeckBuilder),
+                (load_deck, setup_ui).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    refresh_ui.run_if(resource_changed::<DeckBuilder>),
+                    scroll_browser,
+                )
+                    .run_if(in_state(GameState::DeckBuilder)),
+            )
+            .add_systems(OnExit(GameState::DeckBuilder), |mut commands: Commands| {
+                commands.remove_resource::<DeckBuilder>();
+            });
+    }
+}
+
+fn load_deck(mut commands: Commands, pkv: Res<PkvStore>, decks: Res<Assets<DeckAsset>>) {
+    let deck = pkv.get_custom_deck().unwrap_or_else(|| {
+        decks
+            .iter()
+            .next()
+            .map(|(_, asset)| CustomDeck::from_asset(asset))
+            .unwrap_or_default()
+    });
+    commands.insert_resource(DeckBuilder { deck, ..default() });
+}
+
+fn button_system(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&DeckBuilderButton>,
+    mut builder: ResMut<DeckBuilder>,
+    limits: Res<DeckLimits>,
+    decks: Res<Assets<DeckAsset>>,
+    mut pkv: ResMut<PkvStore>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(button) = buttons.get(trigger.target()) else {
+        return;
+    };
+    builder.message = None;
+    match *button {
+        DeckBuilderButton::Add(id) => {
+            if !builder.deck.add(id, &limits) {
+                builder.message = Some(format!(
+                    "Limit is {} copies and {} cards",
+                    limits.max_copies, limits.max_size
+                ));
+            }
+        }
+        DeckBuilderButton::Remove(id) => builder.deck.remove(id),
+        DeckBuilderButton::ResourceFilter => builder.next_resource(),
+        DeckBuilderButton::CostFilter => builder.cost = builder.cost.next(),
+        DeckBuilderButton::Reset => {
+            if let Some((_, asset)) = decks.iter().next() {
+                builder.deck = CustomDeck::from_asset(asset);
+            }
+        }
+        DeckBuilderButton::Save => {
+            builder.message = if builder.deck.is_valid(&limits) {
+                builder.deck.save(&mut pkv);
+                Some("Deck saved".to_owned())
+            } else {
+                Some(format!(
+                    "Deck needs {}-{} cards",
+                    limits.min_size, limits.max_size
+                ))
+            };
+        }
+        DeckBuilderButton::Back => next_state.set(GameState::Menu),
+    }
+}
+
+#[derive(SystemParam)]
+struct BuilderNodes<'w, 's> {
+    browser: Single<'w, Entity, With<CardBrowser>>,
+    list: Single<'w, Entity, With<DeckList>>,
+    status: Single<'w, &'static mut Text, With<DeckStatus>>,
+    labels: Query<'w, 's, (&'static mut Text, &'static FilterLabel), Without<DeckStatus>>,
+}
+
+fn refresh_ui(
+    mut commands: Commands,
+    builder: Res<DeckBuilder>,
+    limits: Res<DeckLimits>,
+    decks: Res<Assets<DeckAsset>>,
+    asset_server: Res<AssetServer>,
+    mut nodes: BuilderNodes,
+) {
+    let Some((_, asset)) = decks.iter().next() else {
+        return;
+    };
+    let font = TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(18.0);
+    let size = builder.deck.size();
+    nodes.status.0 = format!(
+        "Cards: {} ({}-{}){}",
+        size,
+        limits.min_size,
+        limits.max_size,
+        builder
+            .message
+            .as_ref()
+            .map(|m| format!("\n{}", m))
+            .unwrap_or_default()
+    );
+    for (mut text, label) in nodes.labels.iter_mut() {
+        text.0 = match label {
+            FilterLabel::Resource => builder.resource_label(),
+            FilterLabel::Cost => builder.cost.label().to_owned(),
+        };
+    }
+
+    commands
+        .entity(*nodes.browser)
+        .despawn_related::<Children>()
+        .with_children(|p| {
+            for card in asset.iter().filter(|c| builder.shows(c)) {
+                let copies = builder.deck.copies(card.id);
+                p.spawn((
+                    Button,
+                    DeckBuilderButton::Add(card.id),
+                    ImageNode {
+                        image: asset_server.load(card.get_image_asset()),
+                        color: if copies > 0 {
+                            Color::WHITE
+                        } else {
+                            Color::linear_rgb(0.5, 0.5, 0.5)
+                        },
+                        ..default()
+                    },
+                    Node {
+                        width: Val::Px(96.0),
+                        height: Val::Px(131.0),
+                        margin: UiRect::all(Val::Px(3.0)),
+                        justify_content: JustifyContent::FlexEnd,
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    Name::new(card.name.clone()),
+                ))
+                .with_child((
+                    Text::new(if copies > 0 {
+                        format!("x{}", copies)
+                    } else {
+                        String::new()
+                    }),
+                    font.clone(),
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ));
+            }
+        });
+
+    commands
+        .entity(*nodes.list)
+        .despawn_related::<Children>()
+        .with_children(|p| {
+            for entry in builder.deck.entries.iter() {
+                let Some(card) = asset.iter().find(|c| c.id == entry.id) else {
+                    continue;
+                };
+                p.spawn((
+                    Button,
+                    DeckBuilderButton::Remove(card.id),
+                    Node {
+                        padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                ))
+                .with_child((
+                    Text::new(format!(
+                        "{} x{} ({} {})",
+                        card.name, entry.copies, card.cost_amount, card.cost_resource
+                    )),
+                    font.clone(),
+                ));
+            }
+        });
+}
+
+fn scroll_browser(
+    mut wheel: EventReader<MouseWheel>,
+    mut browser: Query<&mut ScrollPosition, With<CardBrowser>>,
+) {
+    for event in wheel.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * 30.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in browser.iter_mut() {
+            scroll.offset_y -= dy;
+        }
+    }
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+
+    commands
+        .spawn(Observer::new(button_system))
+        .insert(StateScoped(GameState::DeckBuilder));
+    commands
+        .spawn(super::root_node())
+        .insert((
+            StateScoped(GameState::DeckBuilder),
+            Name::new("deck-builder-root"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        max_height: Val::Percent(95.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("Deck Builder"), header_style));
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(10.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            for (button, label) in [
+                                (DeckBuilderButton::ResourceFilter, FilterLabel::Resource),
+                                (DeckBuilderButton::CostFilter, FilterLabel::Cost),
+                            ] {
+                                row.spawn((super::button(&asset_server), button))
+                                    .with_child((
+                                        Text::default(),
+                                        label,
+                                        button_style.clone(),
+                                        clr,
+                                    ));
+                            }
+                            row.spawn((
+                                Text::default(),
+                                DeckStatus,
+                                TextFont::from_font(asset_server.load(consts::REGULAR_FONT))
+                                    .with_font_size(20.0),
+                                TextColor(Srgba::hex("fcfd9e").unwrap().into()),
+                            ));
+                        });
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(10.0),
+                            min_height: Val::Px(0.0),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            row.spawn((
+                                Node {
+                                    width: Val::Px(820.0),
+                                    flex_wrap: FlexWrap::Wrap,
+                                    align_content: AlignContent::FlexStart,
+                                    overflow: Overflow::scroll_y(),
+                                    ..default()
+                                },
+                                CardBrowser,
+                                Name::new("card-browser"),
+                            ));
+                            row.spawn((
+                                Node {
+                                    width: Val::Px(320.0),
+                                    flex_direction: FlexDirection::Column,
+                                    overflow: Overflow::scroll_y(),
+                                    ..default()
+                                },
+                                DeckList,
+                                Name::new("deck-list"),
+                            ));
+                        });
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            for (button, text) in [
+                                (DeckBuilderButton::Reset, "Reset"),
+                                (DeckBuilderButton::Save, "Save"),
+                                (DeckBuilderButton::Back, "Back"),
+                            ] {
+                                row.spawn((super::button(&asset_server), button))
+                                    .with_child((Text::new(text), button_style.clone(), clr));
+                            }
+                        });
+                });
+        });
+}
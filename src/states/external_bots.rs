@@ -0,0 +1,59 @@
+//! Players driven by external programs, see [`game_core::ai::external`] for the protocol.
+use super::game::{GameMode, HumanPlayer, PlayerNumber};
+use super::game_states::GameState;
+use super::replays::ReplayPlayback;
+use bevy::prelude::*;
+use game_core::ai::AiPlayer;
+use game_core::ai::external::ExternalBot;
+
+pub struct ExternalBotsPlugin;
+
+/// Commands starting the bots of both players, set with
+/// `--bot-first <command>` and `--bot-second <command>`.
+#[derive(Resource, Debug, Default, Deref)]
+pub struct BotCommands(pub [Option<String>; 2]);
+
+impl BotCommands {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let command = |flag: &str| {
+            args.iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+                .cloned()
+        };
+        Self([command("--bot-first"), command("--bot-second")])
+    }
+}
+
+impl Plugin for ExternalBotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BotCommands::from_args()).add_systems(
+            OnEnter(GameState::Game),
+            attach_bots
+                .after(super::game::init_players)
+                .run_if(not(resource_exists::<ReplayPlayback>))
+                .run_if(not(resource_equals(GameMode::Online))),
+        );
+    }
+}
+
+fn attach_bots(
+    mut commands: Commands,
+    bots: Res<BotCommands>,
+    players: Query<(Entity, &PlayerNumber)>,
+) -> Result {
+    for (e, nr) in players.iter() {
+        let Some(command) = &bots[nr.index()] else {
+            continue;
+        };
+        let bot = ExternalBot::spawn(command)
+            .map_err(|err| format!("Failed to start bot `{}`: {}", command, err))?;
+        info!("{:?} player is driven by `{}`", nr, command);
+        commands
+            .entity(e)
+            .remove::<HumanPlayer>()
+            .insert(AiPlayer(Box::new(bot)));
+    }
+    Ok(())
+}
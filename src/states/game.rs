@@ -1,33 +1,57 @@
 use super::consts;
 use super::game_states::GameState;
-use crate::base_systems::turn_based::{ActorTurn, CurrentActorToken, GameTurnSteps};
+use super::pause::GamePaused;
+use crate::base_systems::turn_based::{ActorTurn, CurrentActorToken, ExtraTurn, GameTurnSteps};
 use crate::components::ObserverExtension;
 use crate::data::deck::{DeckAsset, HandCards};
 use crate::data::profile::Profile;
+use crate::data::saved_match::ResumedMatch;
+use crate::data::seed::{MatchSeed, SeededRng};
 use crate::helpers::AudioSpawnCommandExt;
 use crate::visual::BackgroundSprite;
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future};
 use bevy::time::Stopwatch;
+use game_core::ai::{AiDifficulty, AiPlayer, AiPolicy, RandomLegal};
 use game_core::data::card::Card;
+use game_core::data::deck::Piles;
 use game_core::data::player::PlayerHealth;
+pub use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
 use game_core::data::supply::PlayerSupply;
-use rand::{Rng, thread_rng};
+pub use game_core::r#match::ActionTaken;
+use game_core::r#match::{MatchEvent, MatchState, RuleError};
+use game_core::rng::GameRng;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    Component, Serialize, Deserialize, PartialEq, Eq, Hash, Copy, Debug, Clone, Reflect, Default,
-)]
-pub enum PlayerNumber {
-    #[default]
-    First,
-    Second,
-}
-
 #[derive(Component)]
 pub struct HumanPlayer;
 
+/// Action being picked on the async compute pool, so slow policies
+/// and external bots don't stall the frames.
+#[derive(Component)]
+struct AiThinking {
+    task: Task<(Box<dyn AiPolicy>, Option<ActionTaken>, GameRng)>,
+    /// Set when the match got paused, the picked action is dropped
+    /// and the thinking starts again after resuming
+    interrupted: bool,
+}
+
+/// Who controls the second player.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub enum GameMode {
+    #[default]
+    VsAi,
+    /// Both players are humans sharing one device.
+    HotSeat,
+    /// Opponent plays on another device, the match is hosted by the server.
+    Online,
+}
+
 pub struct GamePlugin;
 
 #[derive(Component)]
@@ -51,22 +75,19 @@ pub struct GameObject;
 pub struct NamesAsset(pub Vec<String>);
 
 impl NamesAsset {
-    pub fn get_random(&self) -> String {
-        let mut rng = thread_rng();
+    pub fn get_random(&self, rng: &mut impl Rng) -> String {
         let i: usize = rng.r#gen::<usize>() % self.0.len();
         self.0[i].clone()
     }
 }
 
-#[derive(Component, Debug, Clone)]
-pub enum ActionTaken {
-    UseCard { card: Card },
-    DropCard { card: Card },
-}
-
 #[derive(Resource, Debug, Default, Reflect)]
 pub struct TimeSinceTurnStarted(pub Stopwatch);
 
+/// Sent for every [`MatchEvent`] produced by the rules when an action is performed.
+#[derive(Event, Debug, Clone, Deref)]
+pub struct MatchUpdate(pub MatchEvent);
+
 #[derive(Component, Debug, Default, Reflect)]
 #[require(GameObject)]
 struct ExitGameTimer(pub Timer);
@@ -75,16 +96,15 @@ struct ExitGameTimer(pub Timer);
 #[reflect(Resource)]
 pub struct OpponentInformation(pub PlayerInformation);
 
-#[derive(Resource, Debug, Default, Reflect)]
+#[derive(Resource, Debug, Default, Reflect, Clone, Serialize, Deserialize)]
 #[reflect(Resource)]
 pub struct PlayerInformation {
     pub name: String,
-    pub start_stats: PlayerHealth,
     pub deck: DeckAsset,
     pub avatar_id: i32,
 }
 
-#[derive(Component, Debug, Default, Reflect)]
+#[derive(Component, Debug, Default, Clone, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
 #[require(PlayerNumber)]
 pub struct PlayerDetailsInfo {
@@ -114,6 +134,7 @@ pub struct PlayerQuery {
     pub details: &'static PlayerDetailsInfo,
     pub player: &'static PlayerHealth,
     pub supply: &'static PlayerSupply,
+    pub piles: &'static Piles,
 }
 
 #[derive(SystemParam, Deref)]
@@ -127,7 +148,35 @@ impl Players<'_, '_> {
 
 #[derive(QueryFilter)]
 pub struct PlayersUpdatedFilter {
-    _a: Or<(Changed<PlayerHealth>, Changed<PlayerSupply>)>,
+    _a: Or<(Changed<PlayerHealth>, Changed<PlayerSupply>, Changed<Piles>)>,
+}
+
+/// Current actor that did not pick the action yet
+#[derive(QueryFilter)]
+pub struct AwaitingActionFilter {
+    _a: With<CurrentActorToken>,
+    _b: Without<ActionTaken>,
+}
+
+/// Components mirroring the [`PlayerState`](game_core::r#match::PlayerState) kept by the match rules.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct PlayerStateQuery {
+    pub nr: &'static PlayerNumber,
+    supply: &'static mut PlayerSupply,
+    health: &'static mut PlayerHealth,
+    hand: &'static mut HandCards,
+    piles: &'static mut Piles,
+}
+
+impl PlayerStateQueryItem<'_> {
+    pub fn sync(&mut self, match_state: &MatchState) {
+        let state = match_state.player(*self.nr);
+        *self.supply = state.supply.clone();
+        *self.health = state.health;
+        *self.hand = state.hand.clone();
+        *self.piles = state.piles.clone();
+    }
 }
 
 #[derive(SystemParam, Deref)]
@@ -149,7 +198,10 @@ impl Plugin for GamePlugin {
             )
             .add_systems(
                 Update,
-                perform_action.run_if(in_state(GameTurnSteps::PerformAction)),
+                perform_action.run_if(
+                    in_state(GameTurnSteps::PerformAction)
+                        .and(not(resource_equals(GameMode::Online))),
+                ),
             )
             .add_systems(
                 OnEnter(GameTurnSteps::ActionSelection),
@@ -162,8 +214,10 @@ impl Plugin for GamePlugin {
             .add_systems(
                 Update,
                 (handle_card_events, ai_select_card)
-                    .run_if(in_state(GameTurnSteps::ActionSelection)),
+                    .run_if(in_state(GameTurnSteps::ActionSelection))
+                    .run_if(not(resource_exists::<GamePaused>)),
             )
+            .add_systems(Update, interrupt_ai.run_if(resource_added::<GamePaused>))
             .add_systems(
                 Update,
                 (
@@ -173,11 +227,20 @@ impl Plugin for GamePlugin {
                 )
                     .run_if(in_state(GameState::Game)),
             )
-            .add_systems(Update, update_timers.run_if(in_state(GameState::Game)))
+            .add_systems(
+                Update,
+                update_timers
+                    .run_if(in_state(GameState::Game).and(not(resource_exists::<GamePaused>))),
+            )
+            .add_event::<MatchUpdate>()
             .init_resource::<SelectedCard>()
             .init_resource::<TimeSinceTurnStarted>()
+            .init_resource::<AiDifficulty>()
+            .init_resource::<Ruleset>()
+            .init_resource::<GameMode>()
+            .register_type::<GameMode>()
+            .register_type::<AiDifficulty>()
             .register_type::<GameObject>()
-            .register_type::<HandCards>()
             .register_type::<ExitGameTimer>()
             .register_type::<OpponentInformation>()
             .register_type::<PlayerInformation>()
@@ -185,16 +248,25 @@ impl Plugin for GamePlugin {
             .register_type::<TimeSinceTurnStarted>()
             .add_systems(
                 Update,
-                esc_to_menu
+                toggle_help
                     .run_if(not(game_ended_condition))
                     .run_if(in_state(GameState::Game)),
             );
     }
 }
 
-fn end_game(query: Query<&PlayerHealth, With<HumanPlayer>>, mut commands: Commands) -> Result {
-    let player = query.single()?;
-    let player_won = player.has_max_possible_tower() || player.is_alive();
+fn end_game(
+    query: Query<&PlayerNumber, With<HumanPlayer>>,
+    match_state: Res<MatchState>,
+    mut commands: Commands,
+) -> Result {
+    let outcome = match_state.outcome().ok_or("Match is not finished")?;
+    // replays have no human player, those are shown from the first player perspective
+    let player_won = if query.is_empty() {
+        outcome.winner == PlayerNumber::default()
+    } else {
+        query.iter().any(|nr| outcome.winner.eq(nr))
+    };
     info!("PLAYER WON? {}", player_won);
     let sound = if player_won {
         "snd/game_won.ogg"
@@ -219,8 +291,8 @@ fn update_timers(
     for mut t in exit_game_timer.iter_mut() {
         t.0.tick(time.delta());
         if t.0.finished() {
-            info!("Timer finished, going back to menu");
-            next_state.set(GameState::Menu);
+            info!("Timer finished, showing the results");
+            next_state.set(GameState::Results);
             t.0.reset();
             continue;
         }
@@ -228,14 +300,13 @@ fn update_timers(
 }
 
 pub fn switch_player(
-    mut q: Query<(&Name, &mut PlayerSupply), With<CurrentActorToken>>,
+    q: Query<&Name, With<CurrentActorToken>>,
     mut timer: ResMut<TimeSinceTurnStarted>,
 ) {
-    let Ok((player, mut resources)) = q.single_mut() else {
+    let Ok(player) = q.single() else {
         return;
     };
     info!("Switch player: {}", player);
-    resources.update_resources();
     timer.0.reset();
 }
 
@@ -264,36 +335,94 @@ pub fn switch_player(
 //     }
 // }
 
+/// Everything picked in the menu (or loaded from a replay) to start the match.
+#[derive(SystemParam)]
+pub struct MatchSetup<'w> {
+    player: Res<'w, PlayerInformation>,
+    opponent: Res<'w, OpponentInformation>,
+    seed: Res<'w, MatchSeed>,
+    rules: Res<'w, Ruleset>,
+    difficulty: Res<'w, AiDifficulty>,
+    mode: Res<'w, GameMode>,
+    resumed: Option<Res<'w, ResumedMatch>>,
+}
+
 pub fn init_players(
     mut commands: Commands,
-    player: Res<PlayerInformation>,
-    opponent: Res<OpponentInformation>,
+    setup: MatchSetup,
     mut next_state: ResMut<NextState<GameTurnSteps>>,
-) {
+    mut game_state: ResMut<NextState<GameState>>,
+) -> Result {
+    let MatchSetup {
+        player,
+        opponent,
+        seed,
+        rules,
+        difficulty,
+        mode,
+        resumed,
+    } = setup;
+    if player.deck.is_empty() || opponent.deck.is_empty() {
+        game_state.set(GameState::Menu);
+        return Err("Cannot start the match, one of the decks is empty".into());
+    }
+    let match_state = match &resumed {
+        Some(resumed) => resumed.state.clone(),
+        None => {
+            let decks = [player.deck.0.clone().into(), opponent.deck.0.clone().into()];
+            MatchState::new(decks, seed.seed, rules.clone())
+        }
+    };
+    // resumed matches keep the details and the turn order of the saved players
+    let saved = |nr| resumed.as_ref().and_then(|r| r.player(nr));
+    let details = |nr, info: &PlayerInformation| {
+        saved(nr).map_or_else(|| PlayerDetailsInfo::from(info), |p| p.details.clone())
+    };
+    let turn = |nr, default| ActorTurn(saved(nr).map_or(default, |p| p.turn));
+    let first_details = details(PlayerNumber::First, &player);
+    let second_details = details(PlayerNumber::Second, &opponent);
+
+    let first = match_state.player(PlayerNumber::First);
     commands
         .spawn((
-            Name::new(player.name.clone()),
-            player.start_stats,
-            PlayerDetailsInfo::from(&*player),
+            Name::new(first_details.name.clone()),
+            first.health,
+            first_details,
             PlayerNumber::First,
             HumanPlayer,
-            ActorTurn(0),
-            PlayerSupply::default(),
-            HandCards::generate_random(&player.deck.0),
-        ))
-        .insert(GameObject);
-    commands
-        .spawn((
-            Name::new(opponent.name.clone()),
-            opponent.start_stats,
-            PlayerNumber::Second,
-            ActorTurn(1),
-            PlayerDetailsInfo::from(&**opponent),
-            PlayerSupply::default(),
-            HandCards::generate_random(&opponent.deck.0),
+            turn(PlayerNumber::First, 0),
+            first.supply.clone(),
+            first.hand.clone(),
+            first.deck.clone(),
+            first.piles.clone(),
         ))
         .insert(GameObject);
+    let second = match_state.player(PlayerNumber::Second);
+    let mut second_entity = commands.spawn((
+        Name::new(second_details.name.clone()),
+        second.health,
+        PlayerNumber::Second,
+        turn(PlayerNumber::Second, 1),
+        second_details,
+        second.supply.clone(),
+        second.hand.clone(),
+        second.deck.clone(),
+        second.piles.clone(),
+        GameObject,
+    ));
+    match *mode {
+        GameMode::VsAi => {
+            second_entity.insert(AiPlayer(difficulty.policy()));
+        }
+        GameMode::HotSeat => {
+            second_entity.insert(HumanPlayer);
+        }
+        // seats are assigned by the server once the match state is received
+        GameMode::Online => {}
+    }
+    commands.insert_resource(match_state);
     next_state.set(GameTurnSteps::SearchForAgents);
+    Ok(())
 }
 
 fn setup_music(asset_server: Res<AssetServer>, mut commands: Commands) {
@@ -312,7 +441,12 @@ fn card_sounds(mut commands: Commands, q: Query<&ActionTaken, Added<ActionTaken>
     }
 }
 
-fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    seed: Res<MatchSeed>,
+    rules: Res<Ruleset>,
+) {
     let header_style =
         TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
     commands.spawn((
@@ -347,20 +481,26 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             p.spawn((
                 TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(25.0),
                 TextColor(Srgba::hex("fcfd9e").unwrap().into()),
-                Text::new(consts::HELP_TEXT),
+                Text::new(consts::help_text(rules.max_tower_hp)),
             ));
         });
+    commands.spawn((
+        Text::new(format!("Seed: {}", seed.seed)),
+        TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(14.0),
+        TextColor(Color::WHITE.with_alpha(0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(15.0),
+            bottom: Val::Px(15.0),
+            ..default()
+        },
+        GameObject,
+        Name::new("Seed display"),
+    ));
 }
 
-fn esc_to_menu(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut query: Query<&mut Node, With<HelpDisplay>>,
-) {
-    if keys.just_released(KeyCode::Escape) {
-        next_state.set(GameState::AssetsLoading);
-        // keys.reset(KeyCode::Escape);
-    } else if keys.just_released(KeyCode::KeyH) {
+fn toggle_help(keys: Res<ButtonInput<KeyCode>>, mut query: Query<&mut Node, With<HelpDisplay>>) {
+    if keys.just_released(KeyCode::KeyH) {
         for mut style in &mut query {
             style.display = if style.display == Display::Flex {
                 Display::None
@@ -373,101 +513,142 @@ fn esc_to_menu(
 }
 
 fn handle_card_events(
-    any_action: Query<&ActionTaken>,
+    any_action: Query<(Entity, &PlayerNumber, &ActionTaken)>,
+    match_state: Res<MatchState>,
     mut next_state: ResMut<NextState<GameTurnSteps>>,
+    mut commands: Commands,
 ) {
-    let Ok(action_to_do) = any_action.single() else {
+    let Ok((e, nr, action_to_do)) = any_action.single() else {
         return;
     };
     info!("{:#?}", action_to_do);
+    let validation = if match_state.current.eq(nr) {
+        match_state.validate(action_to_do).map(|_| ())
+    } else {
+        Err(RuleError::NotPlayerTurn(*nr))
+    };
+    if let Err(err) = validation {
+        warn!("Action {:?} rejected: {}", action_to_do, err);
+        commands.entity(e).remove::<ActionTaken>();
+        return;
+    }
     next_state.set(GameTurnSteps::PerformAction);
 }
 
+fn interrupt_ai(mut thinking: Query<&mut AiThinking>) {
+    for mut thinking in thinking.iter_mut() {
+        thinking.interrupted = true;
+    }
+}
+
 fn ai_select_card(
     mut commands: Commands,
-    cur_player_q: Query<(&CurrentActorToken, &HandCards, Entity), Without<HumanPlayer>>,
+    mut cur_player_q: Query<
+        (
+            &PlayerNumber,
+            &mut AiPlayer,
+            Entity,
+            Option<&mut AiThinking>,
+        ),
+        AwaitingActionFilter,
+    >,
+    mut match_state: ResMut<MatchState>,
     time_since: Res<TimeSinceTurnStarted>,
+    mut rng: ResMut<SeededRng>,
+    mut updates: EventWriter<MatchUpdate>,
+    mut next_state: ResMut<NextState<GameTurnSteps>>,
     mut random_wait_time: Local<f32>,
 ) {
-    let Ok((_, hand, e)) = cur_player_q.single() else {
+    let Ok((nr, mut ai, e, thinking)) = cur_player_q.single_mut() else {
         return;
     };
+    let Some(mut thinking) = thinking else {
+        // the policy and the rng are moved to the task and given back with the action
+        let mut policy = std::mem::replace(&mut ai.0, Box::new(RandomLegal));
+        let mut ai_rng = rng.0.clone();
+        let state = match_state.clone();
+        let nr = *nr;
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let action = policy.select_action(&state.view(nr), &mut ai_rng);
+            (policy, action, ai_rng)
+        });
+        commands.entity(e).insert(AiThinking {
+            task,
+            interrupted: false,
+        });
+        return;
+    };
+    if thinking.interrupted {
+        let Some((policy, _, _)) = block_on(future::poll_once(&mut thinking.task)) else {
+            return;
+        };
+        ai.0 = policy;
+        commands.entity(e).remove::<AiThinking>();
+        return;
+    }
     if *random_wait_time < 1.0 {
         *random_wait_time = 1.5; // TODO make it random
     }
     if time_since.0.elapsed_secs() < *random_wait_time {
         return;
     }
-    let i = hand.rnd();
-    let random_card = hand[i].clone();
-
-    commands
-        .entity(e)
-        .insert(ActionTaken::UseCard { card: random_card });
+    let Some((policy, action, ai_rng)) = block_on(future::poll_once(&mut thinking.task)) else {
+        return;
+    };
+    ai.0 = policy;
+    rng.0 = ai_rng;
+    commands.entity(e).remove::<AiThinking>();
+    match action {
+        Some(action) => {
+            commands.entity(e).insert(action);
+        }
+        // without cards there is nothing to play, the AI gives up
+        None => match match_state.concede(*nr) {
+            Ok(events) => {
+                updates.write_batch(events.into_iter().map(MatchUpdate));
+                next_state.set(GameTurnSteps::SearchForAgents);
+            }
+            Err(err) => warn!("Cannot concede: {}", err),
+        },
+    }
 }
 
 pub fn perform_action(
-    any_action: Query<&ActionTaken>,
-    mut players_q: Query<(
-        Entity,
-        &mut PlayerSupply,
-        &mut PlayerHealth,
-        &mut HandCards,
-        Option<&CurrentActorToken>,
-    )>,
+    any_action: Query<(Entity, &PlayerNumber, &ActionTaken)>,
+    mut players_q: Query<PlayerStateQuery>,
+    mut match_state: ResMut<MatchState>,
     mut next_state: ResMut<NextState<GameTurnSteps>>,
-    deck: Res<Assets<DeckAsset>>,
+    mut updates: EventWriter<MatchUpdate>,
     mut commands: Commands,
 ) {
-    let Ok(action_to_do) = any_action.single() else {
+    let Ok((e, nr, action_to_do)) = any_action.single() else {
         return;
     };
-    let Some(deck_asset) = deck.iter().next() else {
-        panic!("NO DECK ASSET");
-    };
-    let cards: &Vec<Card> = deck_asset.1.as_ref();
-    for (e, mut res, mut player, mut hand, is_caller) in players_q.iter_mut() {
-        let is_user = is_caller.is_some();
-        let card_id;
-        match action_to_do {
-            ActionTaken::UseCard { card } => {
-                if is_caller.is_some() {
-                    res.change_resource_amount(card.cost_resource, -card.cost_amount);
+    match match_state.apply_as(*nr, action_to_do) {
+        Ok(events) => {
+            for event in events {
+                info!("{:?}", event);
+                if matches!(event, MatchEvent::TurnStarted { player, .. } if player == *nr) {
+                    commands.entity(e).insert(ExtraTurn);
                 }
-                card_id = hand.iter().position(|c| c == card);
-                let res_change = card.resource_amount_change(is_user);
-                res.change_resource_amount(res_change.0, res_change.1);
-                let prod = card.production_change(is_user);
-                res.change_resource_production(prod.0, prod.1);
-                let damage = card.damage(is_user);
-                player.give_damage(damage.0, damage.1);
-                player.make_tower_higher(card.tower_growth(is_user));
-                player.make_walls_higher(card.walls_growth(is_user));
-            }
-            ActionTaken::DropCard { card } => card_id = hand.iter().position(|c| c == card),
-        };
-        if is_user {
-            if let Some(id) = card_id {
-                hand.replace_card(id, &res, cards);
+                updates.write(MatchUpdate(event));
             }
-            commands.entity(e).remove::<ActionTaken>();
         }
+        Err(err) => warn!("Action {:?} rejected: {}", action_to_do, err),
+    }
+    commands.entity(e).remove::<ActionTaken>();
+    for mut player in players_q.iter_mut() {
+        player.sync(&match_state);
     }
     next_state.set(GameTurnSteps::SearchForAgents);
 }
 
 pub fn game_ended_condition(
     state: Option<Res<State<GameState>>>,
-    query: Query<&PlayerHealth>,
+    match_state: Option<Res<MatchState>>,
 ) -> bool {
     if !state.is_some_and(|e| e.eq(&GameState::Game)) {
         return false;
     }
-    for player in &query {
-        if !player.is_alive() || player.has_max_possible_tower() {
-            return true;
-        }
-    }
-
-    false
+    match_state.is_some_and(|m| m.is_finished())
 }
@@ -2,29 +2,54 @@ use crate::base_systems::buttons::ButtonsPlugin;
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 use super::{
-    game::GamePlugin, loading::LoadingPlugin, menu::MenuPlugin,
-    profile_selection::ProfileSelectionPlugin,
+    deck_builder::DeckBuilderPlugin, game::GamePlugin, hot_seat::HotSeatPlugin,
+    loading::LoadingPlugin, menu::MenuPlugin, online::OnlinePlugin, pause::PausePlugin,
+    profile_selection::ProfileSelectionPlugin, replays::ReplaysPlugin, results::ResultsPlugin,
+    resume::ResumePlugin, statistics::StatisticsPlugin,
 };
 
 #[derive(Clone, Debug, Default, Hash, Eq, States, PartialEq)]
 pub enum GameState {
     #[default]
     AssetsLoading,
+    /// Loads images of the content listed by the assets loaded in the previous state
+    ContentLoading,
     Menu,
+    /// Lists the local profiles, shown before the menu
+    ProfileSelection,
     ProfileEdit,
     Game,
+    /// Winner and castle stats of the match that just ended
+    Results,
+    Replays,
+    /// Match history and statistics of the selected profile
+    Statistics,
+    DeckBuilder,
+    /// Waits for the server to pair the player with an opponent
+    Lobby,
 }
 
 pub struct GamePlugins;
 
 impl PluginGroup for GamePlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>()
+        let group = PluginGroupBuilder::start::<Self>()
             .add(ButtonsPlugin)
             .add(MenuPlugin)
             .add(GamePlugin)
+            .add(HotSeatPlugin)
+            .add(OnlinePlugin)
+            .add(PausePlugin)
             .add(LoadingPlugin)
             .add(ProfileSelectionPlugin)
-            .add(crate::components::card_display::CardPlugin)
+            .add(ReplaysPlugin)
+            .add(ResultsPlugin)
+            .add(ResumePlugin)
+            .add(StatisticsPlugin)
+            .add(DeckBuilderPlugin)
+            .add(crate::components::card_display::CardPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        let group = group.add(super::external_bots::ExternalBotsPlugin);
+        group
     }
 }
@@ -0,0 +1,115 @@
+use super::consts;
+use super::game::{GameMode, GameObject, HumanPlayer, PlayerDetailsInfo};
+use super::game_states::GameState;
+use crate::base_systems::turn_based::{CurrentActorToken, GameTurnSteps};
+use crate::components::ObserverExtension;
+use crate::components::player_ui::{PlayerPanel, panel_node};
+use crate::helpers::button::ButtonReleased;
+use bevy::ecs::query::QueryFilter;
+use bevy::prelude::*;
+use game_core::data::player::PlayerNumber;
+
+pub struct HotSeatPlugin;
+
+/// Player that was last handed the device, the pass screen is skipped when
+/// the same player gets another turn.
+#[derive(Resource, Debug, Default, Deref)]
+struct DeviceHolder(Option<PlayerNumber>);
+
+/// Covers the table until the next player confirms they hold the device.
+#[derive(Component)]
+#[require(GameObject, Name = Name::new("Pass device screen"))]
+struct PassDeviceScreen;
+
+/// Human player that has to pick the action now.
+#[derive(QueryFilter)]
+struct HumanActorFilter {
+    _a: With<CurrentActorToken>,
+    _b: With<HumanPlayer>,
+}
+
+impl Plugin for HotSeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Game), reset_holder)
+            .add_systems(
+                OnEnter(GameTurnSteps::ActionSelection),
+                (show_pass_screen, follow_current_actor)
+                    .after(super::game::switch_player)
+                    .run_if(resource_equals(GameMode::HotSeat)),
+            );
+    }
+}
+
+fn reset_holder(mut commands: Commands) {
+    commands.insert_resource(DeviceHolder::default());
+}
+
+fn show_pass_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    actor: Query<(&PlayerNumber, &PlayerDetailsInfo), HumanActorFilter>,
+    holder: Res<DeviceHolder>,
+) {
+    let Ok((nr, details)) = actor.single() else {
+        return;
+    };
+    if holder.eq(&Some(*nr)) {
+        return;
+    }
+    let header = TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let regular = TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(25.0);
+    commands
+        .spawn((
+            super::root_node(),
+            BackgroundColor(Srgba::hex("1b1b1b").unwrap().into()),
+            GlobalZIndex(100),
+            PassDeviceScreen,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(format!("Pass the device to {}", details.name)),
+                header,
+            ));
+            p.spawn((
+                Text::new("Cards of the previous player are hidden"),
+                regular.clone(),
+                TextColor(Srgba::hex("fcfd9e").unwrap().into()),
+                Node {
+                    margin: UiRect::vertical(Val::Px(15.0)),
+                    ..default()
+                },
+            ));
+            p.spawn(super::button(&asset_server))
+                .with_child((
+                    Text::new("Ready"),
+                    regular,
+                    TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
+                ))
+                .observe_in_child(hand_over_device);
+        });
+}
+
+fn hand_over_device(
+    _: Trigger<ButtonReleased>,
+    mut commands: Commands,
+    screen: Query<Entity, With<PassDeviceScreen>>,
+    actor: Query<&PlayerNumber, With<CurrentActorToken>>,
+) {
+    for e in screen.iter() {
+        commands.entity(e).despawn();
+    }
+    commands.insert_resource(DeviceHolder(actor.single().ok().copied()));
+}
+
+/// Panel of the player holding the device is always on the left side.
+fn follow_current_actor(
+    actor: Query<&PlayerNumber, HumanActorFilter>,
+    mut panels: Query<(&PlayerPanel, &mut Node)>,
+) {
+    let Ok(nr) = actor.single() else {
+        return;
+    };
+    for (panel, mut node) in panels.iter_mut() {
+        *node = panel_node(panel.0 != *nr);
+    }
+}
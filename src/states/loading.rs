@@ -1,9 +1,12 @@
 use super::{game::NamesAsset, game_states::GameState};
+use crate::data::profile::{AvatarsAsset, Profile};
 use crate::{data::deck::DeckAsset, states};
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use bevy_asset_loader::asset_collection::AssetCollection;
 use bevy_asset_loader::prelude::*;
 use consts::{FULL_SIZE_PERCENT, LABEL_FONT};
+use game_core::data::ruleset::RulesetsAsset;
 use states::consts;
 
 #[allow(dead_code)]
@@ -11,45 +14,6 @@ use states::consts;
 pub struct BaseAssets {
     #[asset(
         paths(
-            "avatars/1.png",
-            "avatars/2.png",
-            "avatars/3.png",
-            "avatars/4.png",
-            "avatars/5.png",
-            "avatars/6.png",
-            "avatars/7.png",
-            "avatars/8.png",
-            "avatars/9.png",
-            "avatars/10.png",
-            "avatars/11.png",
-            "avatars/12.png",
-            "avatars/13.png",
-            "cards/1.png",
-            "cards/2.png",
-            "cards/3.png",
-            "cards/4.png",
-            "cards/5.png",
-            "cards/6.png",
-            "cards/7.png",
-            "cards/8.png",
-            "cards/9.png",
-            "cards/10.png",
-            "cards/11.png",
-            "cards/12.png",
-            "cards/13.png",
-            "cards/14.png",
-            "cards/15.png",
-            "cards/16.png",
-            "cards/17.png",
-            "cards/18.png",
-            "cards/19.png",
-            "cards/20.png",
-            "cards/21.png",
-            "cards/22.png",
-            "cards/23.png",
-            "cards/24.png",
-            "cards/25.png",
-            "cards/26.png",
             "img/panel-004.png",
             "img/panel-006.png",
             "img/start_screen_bg.png",
@@ -69,22 +33,74 @@ pub struct BaseAssets {
     pub deck: Handle<DeckAsset>,
     #[asset(path = "enemy.names.json")]
     pub names: Handle<NamesAsset>,
+    #[asset(path = "all.avatars.json")]
+    pub avatars: Handle<AvatarsAsset>,
+    #[asset(path = "all.rules.json")]
+    pub rulesets: Handle<RulesetsAsset>,
 }
 
+/// Card and avatar images listed by the assets loaded in [`BaseAssets`].
+#[derive(Resource, Debug, Default, Deref)]
+pub struct ContentAssets(Vec<UntypedHandle>);
+
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
         app.add_loading_state(
             LoadingState::new(GameState::AssetsLoading)
-                .continue_to_state(GameState::Menu)
+                .continue_to_state(GameState::ContentLoading)
                 .load_collection::<BaseAssets>(),
         )
-        .add_systems(OnEnter(GameState::AssetsLoading), setup_ui);
+        .add_systems(OnEnter(GameState::AssetsLoading), setup_ui)
+        .add_systems(OnEnter(GameState::ContentLoading), queue_content)
+        .add_systems(
+            Update,
+            wait_for_content.run_if(in_state(GameState::ContentLoading)),
+        );
         //            .add_system(update_progress.in_set(OnUpdate(GameState::AssetsLoading)));
     }
 }
 
+fn queue_content(
+    mut commands: Commands,
+    base: Res<BaseAssets>,
+    decks: Res<Assets<DeckAsset>>,
+    avatars: Res<Assets<AvatarsAsset>>,
+    asset_server: Res<AssetServer>,
+) -> Result {
+    let deck = decks.get(&base.deck).ok_or("Missing deck asset")?;
+    let avatars = avatars.get(&base.avatars).ok_or("Missing avatars asset")?;
+    let handles = deck
+        .iter()
+        .map(|card| card.get_image_asset())
+        .chain(avatars.iter().map(|id| Profile::format_avatar_path(*id)))
+        .map(|path| asset_server.load::<Image>(path).untyped())
+        .collect();
+    commands.insert_resource(ContentAssets(handles));
+    Ok(())
+}
+
+fn wait_for_content(
+    content: Res<ContentAssets>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let all_done = content
+        .iter()
+        .all(|handle| match asset_server.get_load_state(handle.id()) {
+            Some(LoadState::Failed(err)) => {
+                warn!("Failed to load {:?}: {}", handle.path(), err);
+                true
+            }
+            Some(LoadState::Loaded) => true,
+            _ => false,
+        });
+    if all_done {
+        next_state.set(GameState::Menu);
+    }
+}
+
 fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2d);
 
@@ -100,7 +116,7 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             },
             BackgroundColor(Srgba::hex("#2c422e").unwrap().into()),
-            StateScoped(GameState::AssetsLoading),
+            StateScoped(GameState::ContentLoading),
         ))
         .with_children(|parent| {
             let header_style = TextFont {
@@ -1,16 +1,22 @@
 use std::time::Duration;
 
-use super::game::{OpponentInformation, PlayerInformation};
+use super::game::{GameMode, OpponentInformation, PlayerInformation};
+use super::resume::continue_match;
 use super::{game::NamesAsset, game_states::GameState};
-use crate::data::deck::DeckAsset;
-use crate::data::profile::ProfileProvider;
+use crate::data::deck::{CustomDeckProvider, DeckAsset, DeckLimits};
+use crate::data::profile::{ActiveProfile, AvatarsAsset, ProfileProvider};
+use crate::data::saved_match::SavedMatchProvider;
+use crate::data::seed::{MatchSeed, SeededRng};
 use crate::helpers::button::ButtonReleased;
 use crate::states::consts::*;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::ui::widget::NodeImageMode;
 use bevy_pkv::PkvStore;
 use bevy_tweening::{Animator, Delay, Tween, lens::TransformScaleLens};
-use rand::{Rng, thread_rng};
+use game_core::ai::AiDifficulty;
+use game_core::data::ruleset::{Ruleset, RulesetsAsset};
+use game_core::rng::RngStream;
 
 pub struct MenuPlugin;
 
@@ -23,42 +29,115 @@ impl Plugin for MenuPlugin {
     }
 }
 
+/// Loaded content used to set up both players of the match.
+#[derive(SystemParam)]
+pub(super) struct MatchContent<'w> {
+    decks: Res<'w, Assets<DeckAsset>>,
+    names: Res<'w, Assets<NamesAsset>>,
+    avatars: Res<'w, Assets<AvatarsAsset>>,
+    limits: Res<'w, DeckLimits>,
+}
+
 fn check_for_profile(
     mut next_state: ResMut<NextState<GameState>>,
     pkv: Res<PkvStore>,
-    deck: Res<Assets<DeckAsset>>,
-    names: Res<Assets<NamesAsset>>,
+    active: Option<Res<ActiveProfile>>,
+    content: MatchContent,
+    mut seed: ResMut<MatchSeed>,
     mut commands: Commands,
 ) -> Result {
-    let Some(profile) = pkv.get_profile() else {
-        next_state.set(GameState::ProfileEdit);
+    let Some(profile) = active.and_then(|_| pkv.get_profile()) else {
+        next_state.set(GameState::ProfileSelection);
         return Ok(());
     };
-    let deck_asset = deck.iter().next().ok_or("Missing deck asset")?;
-    let name_asset = names.iter().next().ok_or("Missing names asset")?;
+    let deck_asset = content.decks.iter().next().ok_or("Missing deck asset")?;
+    let player_deck = pkv
+        .get_custom_deck()
+        .filter(|d| d.is_valid(&content.limits))
+        .map(|d| d.build(deck_asset.1))
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| deck_asset.1.clone());
     commands.insert_resource(PlayerInformation {
         name: profile.name.clone(),
         avatar_id: profile.avatar_id,
-        deck: deck_asset.1.clone(),
-        ..Default::default()
+        deck: player_deck,
     });
-    let mut rng = thread_rng();
-    let avatar_id: i32 = 1 + (rng.r#gen::<i32>().abs() % (AVATARS_AMOUNT - 1));
-    commands.insert_resource(OpponentInformation(PlayerInformation {
-        name: name_asset.1.get_random(),
-        deck: deck_asset.1.clone(),
-        avatar_id,
-        ..Default::default()
-    }));
+    roll_match(&mut seed, &content, &mut commands, true)
+}
+
+/// Rolls the seed of the next match, with `new_opponent`
+/// a random opponent replaces the previous one.
+pub(super) fn roll_match(
+    seed: &mut MatchSeed,
+    content: &MatchContent,
+    commands: &mut Commands,
+    new_opponent: bool,
+) -> Result {
+    seed.roll();
+    info!("Match seed: {}", seed.seed);
+    if new_opponent {
+        let deck_asset = content.decks.iter().next().ok_or("Missing deck asset")?;
+        let name_asset = content.names.iter().next().ok_or("Missing names asset")?;
+        let avatars_asset = content
+            .avatars
+            .iter()
+            .next()
+            .ok_or("Missing avatars asset")?;
+        let mut rng = seed.rng(RngStream::Opponent);
+        let avatar_id = avatars_asset.1.random(&mut rng);
+        commands.insert_resource(OpponentInformation(PlayerInformation {
+            name: name_asset.1.get_random(&mut rng),
+            deck: deck_asset.1.clone(),
+            avatar_id,
+        }));
+    }
+    commands.insert_resource(SeededRng(seed.rng(RngStream::Ai)));
     Ok(())
 }
 
-fn start_game(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+fn start_game(
+    _: Trigger<ButtonReleased>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    *mode = GameMode::VsAi;
     next_state.set(GameState::Game);
 }
 
-fn edit_profile_game(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
-    next_state.set(GameState::ProfileEdit);
+fn start_hot_seat(
+    _: Trigger<ButtonReleased>,
+    mut mode: ResMut<GameMode>,
+    mut opponent: ResMut<OpponentInformation>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    *mode = GameMode::HotSeat;
+    opponent.0.name = "Player 2".to_owned();
+    next_state.set(GameState::Game);
+}
+
+fn start_online(
+    _: Trigger<ButtonReleased>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    *mode = GameMode::Online;
+    next_state.set(GameState::Lobby);
+}
+
+fn open_deck_builder(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::DeckBuilder);
+}
+
+fn open_replays(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Replays);
+}
+
+fn open_statistics(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Statistics);
+}
+
+fn open_profiles(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::ProfileSelection);
 }
 
 fn open_repo(_: Trigger<ButtonReleased>) {
@@ -70,7 +149,55 @@ fn exit_game(_: Trigger<ButtonReleased>, mut exit: EventWriter<AppExit>) {
     exit.write(AppExit::Success);
 }
 
-fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn cycle_difficulty(
+    trigger: Trigger<ButtonReleased>,
+    mut difficulty: ResMut<AiDifficulty>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    *difficulty = difficulty.next();
+    for child in children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = texts.get_mut(child) {
+            text.0 = difficulty_label(*difficulty);
+        }
+    }
+}
+
+fn difficulty_label(difficulty: AiDifficulty) -> String {
+    format!("Difficulty: {}", difficulty)
+}
+
+fn cycle_rules(
+    trigger: Trigger<ButtonReleased>,
+    mut rules: ResMut<Ruleset>,
+    rulesets: Res<Assets<RulesetsAsset>>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) -> Result {
+    let (_, rulesets) = rulesets.iter().next().ok_or("Missing rulesets asset")?;
+    let Some(next) = rulesets.next(&rules.name) else {
+        return Ok(());
+    };
+    *rules = next.clone();
+    for child in children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = texts.get_mut(child) {
+            text.0 = rules_label(&rules);
+        }
+    }
+    Ok(())
+}
+
+fn rules_label(rules: &Ruleset) -> String {
+    format!("Rules: {}", rules.name)
+}
+
+fn setup_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    difficulty: Res<AiDifficulty>,
+    rules: Res<Ruleset>,
+    pkv: Res<PkvStore>,
+) {
     commands
         .spawn(super::root_node())
         .insert(StateScoped(GameState::Menu))
@@ -119,34 +246,105 @@ fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
             let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
             let mut start_time_ms = 500;
 
-            for (text, margin, observer) in [
+            let mut entries = vec![
                 (
-                    "Quick Fight",
+                    "Quick Fight".to_owned(),
                     UiRect {
-                        top: Val::Auto,
                         bottom: Val::Px(15.0),
                         ..default()
                     },
                     Observer::new(start_game),
                 ),
                 (
-                    "Edit Profile",
+                    "Hot Seat".to_owned(),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(start_hot_seat),
+                ),
+                (
+                    "Online".to_owned(),
                     UiRect {
                         bottom: Val::Px(15.0),
                         ..default()
                     },
-                    Observer::new(edit_profile_game),
+                    Observer::new(start_online),
+                ),
+                (
+                    difficulty_label(*difficulty),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(cycle_difficulty),
+                ),
+                (
+                    rules_label(&rules),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(cycle_rules),
+                ),
+                (
+                    "Deck Builder".to_owned(),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(open_deck_builder),
+                ),
+                (
+                    "Replays".to_owned(),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(open_replays),
+                ),
+                (
+                    "Statistics".to_owned(),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(open_statistics),
+                ),
+                (
+                    "Profiles".to_owned(),
+                    UiRect {
+                        bottom: Val::Px(15.0),
+                        ..default()
+                    },
+                    Observer::new(open_profiles),
                 ),
                 #[cfg(not(target_arch = "wasm32"))]
                 (
-                    "Exit Game",
+                    "Exit Game".to_owned(),
                     UiRect {
                         bottom: Val::Px(15.0),
                         ..default()
                     },
                     Observer::new(exit_game),
                 ),
-            ] {
+            ];
+            if pkv.has_saved_match() {
+                entries.insert(
+                    0,
+                    (
+                        "Continue".to_owned(),
+                        UiRect {
+                            bottom: Val::Px(15.0),
+                            ..default()
+                        },
+                        Observer::new(continue_match),
+                    ),
+                );
+            }
+            // pushes the buttons to the bottom of the screen
+            entries[0].1.top = Val::Auto;
+            for (text, margin, observer) in entries {
                 let tween_scale = Tween::new(
                     EaseFunction::QuadraticInOut,
                     Duration::from_millis(500),
@@ -1,12 +1,23 @@
 use crate::states::consts::FULL_SIZE_PERCENT;
-use bevy::prelude::{AlignContent, AlignItems, FlexDirection, JustifyContent, Node, default};
+use bevy::prelude::*;
+use bevy::ui::widget::NodeImageMode;
 
 pub mod consts;
+pub mod deck_builder;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod external_bots;
 pub mod game;
 pub mod game_states;
+pub mod hot_seat;
 pub mod loading;
 pub mod menu;
+pub mod online;
+pub mod pause;
 pub mod profile_selection;
+pub mod replays;
+pub mod results;
+pub mod resume;
+pub mod statistics;
 
 pub(super) fn root_node() -> Node {
     Node {
@@ -19,3 +30,26 @@ pub(super) fn root_node() -> Node {
         ..default()
     }
 }
+
+pub(super) fn button(asset_server: &AssetServer) -> impl Bundle {
+    (
+        Button,
+        ImageNode {
+            image_mode: NodeImageMode::Sliced(TextureSlicer {
+                border: BorderRect::all(29.0),
+                center_scale_mode: SliceScaleMode::Stretch,
+                sides_scale_mode: SliceScaleMode::Stretch,
+                max_corner_scale: 1.0,
+            }),
+            color: Srgba::hex("7A444A").unwrap().into(),
+            image: asset_server.load("img/panel-006.png"),
+            ..Default::default()
+        },
+        Node {
+            padding: UiRect::all(Val::Px(15.0)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    )
+}
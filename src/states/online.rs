@@ -0,0 +1,413 @@
+//! Matches against remote players, hosted by the `server` crate.
+//!
+//! The server owns the match, so the actions picked by the local player are sent
+//! to it and both players are updated only with the state it sends back.
+use super::consts;
+use super::game::{
+    ActionTaken, GameMode, HumanPlayer, MatchUpdate, OpponentInformation, PlayerInformation,
+    PlayerStateQuery,
+};
+use super::game_states::GameState;
+use crate::base_systems::turn_based::{CurrentActorToken, ExtraTurn, GameTurnSteps};
+use crate::data::deck::{DeckAsset, DeckEntry};
+use crate::data::seed::SeededRng;
+use crate::helpers::button::ButtonReleased;
+use bevy::prelude::*;
+use ewebsock::{WsEvent, WsMessage, WsReceiver, WsSender};
+use game_core::ai::AiPlayer;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
+use game_core::r#match::{Events, MatchEvent, MatchState};
+use game_core::protocol::{
+    ClientMessage, MatchSnapshot, PROTOCOL_VERSION, ProtocolError, ServerMessage,
+};
+use std::collections::VecDeque;
+
+const DEFAULT_SERVER_URL: &str = "ws://127.0.0.1:9001";
+
+pub struct OnlinePlugin;
+
+/// Address of the match server, set with `--server <url>`.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct ServerUrl(pub String);
+
+impl ServerUrl {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let url = args
+            .iter()
+            .position(|a| a == "--server")
+            .and_then(|i| args.get(i + 1))
+            .cloned();
+        Self(url.unwrap_or_else(|| DEFAULT_SERVER_URL.to_owned()))
+    }
+}
+
+/// Open connection to the server. It is not `Send` on the web, so it is kept as a non-send resource.
+struct ServerConnection {
+    sender: WsSender,
+    receiver: WsReceiver,
+}
+
+/// Progress of the online match, exists from entering the lobby until going back to the menu.
+#[derive(Resource, Debug, Default)]
+pub struct OnlineMatch {
+    pub me: PlayerNumber,
+    pub status: String,
+    /// State received when the match started
+    start: Option<MatchSnapshot>,
+    /// Updates received from the server that were not applied yet
+    pending: VecDeque<(Events, MatchSnapshot)>,
+    /// Last action sent by the local player was rejected by the server
+    rejected: bool,
+}
+
+/// Written when the local player concedes, so the server ends the match for both.
+#[derive(Event, Debug)]
+pub struct OnlineConcede;
+
+/// Player whose actions are received from the server.
+#[derive(Component, Debug, Default)]
+pub struct RemotePlayer;
+
+#[derive(Component)]
+struct LobbyStatus;
+
+impl Plugin for OnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ServerUrl::from_args())
+            .add_event::<OnlineConcede>()
+            .add_systems(OnEnter(GameState::Lobby), (connect, setup_lobby_ui))
+            .add_systems(OnEnter(GameState::Menu), disconnect)
+            .add_systems(
+                Update,
+                (receive_messages, send_concede).run_if(resource_exists::<OnlineMatch>),
+            )
+            .add_systems(
+                Update,
+                update_lobby_status.run_if(in_state(GameState::Lobby)),
+            )
+            .add_systems(
+                OnEnter(GameState::Game),
+                prepare_online_match
+                    .after(super::game::init_players)
+                    .run_if(resource_equals(GameMode::Online)),
+            )
+            .add_systems(
+                Update,
+                (
+                    await_remote_action.run_if(in_state(GameTurnSteps::ActionSelection)),
+                    (send_action, apply_update)
+                        .chain()
+                        .run_if(in_state(GameTurnSteps::PerformAction)),
+                )
+                    .run_if(resource_exists::<OnlineMatch>.and(in_state(GameState::Game))),
+            );
+    }
+}
+
+fn connect(world: &mut World) {
+    let url = world.resource::<ServerUrl>().0.clone();
+    let status = match ewebsock::connect(url.clone(), ewebsock::Options::default()) {
+        Ok((sender, receiver)) => {
+            world.insert_non_send_resource(ServerConnection { sender, receiver });
+            format!("Connecting to {}", url)
+        }
+        Err(err) => format!("Failed to connect to {}: {}", url, err),
+    };
+    world.insert_resource(OnlineMatch {
+        status,
+        ..default()
+    });
+}
+
+fn disconnect(world: &mut World) {
+    world.remove_non_send_resource::<ServerConnection>();
+    world.remove_resource::<OnlineMatch>();
+}
+
+fn send(connection: &mut ServerConnection, message: &ClientMessage) {
+    match serde_json::to_string(message) {
+        Ok(json) => connection.sender.send(WsMessage::Text(json)),
+        Err(err) => error!("Failed to serialize message: {}", err),
+    }
+}
+
+fn receive_messages(
+    connection: Option<NonSendMut<ServerConnection>>,
+    mut online: ResMut<OnlineMatch>,
+    mut player: ResMut<PlayerInformation>,
+    rules: Res<Ruleset>,
+    mut commands: Commands,
+    game_state: Res<State<GameState>>,
+    match_state: Option<Res<MatchState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    let in_game = game_state.get() == &GameState::Game;
+    while let Some(event) = connection.receiver.try_recv() {
+        let message = match event {
+            WsEvent::Opened => {
+                online.status = format!("Waiting for an opponent with {} rules", rules.name);
+                let join = ClientMessage::Join {
+                    version: PROTOCOL_VERSION,
+                    name: player.name.clone(),
+                    avatar_id: player.avatar_id,
+                    rules: rules.name.clone(),
+                    deck: player
+                        .deck
+                        .iter()
+                        .map(|card| DeckEntry {
+                            id: card.id,
+                            copies: card.copies,
+                        })
+                        .collect(),
+                };
+                send(&mut connection, &join);
+                continue;
+            }
+            WsEvent::Message(WsMessage::Text(text)) => {
+                match serde_json::from_str::<ServerMessage>(&text) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        warn!("Invalid server message: {}", err);
+                        continue;
+                    }
+                }
+            }
+            WsEvent::Message(_) => continue,
+            WsEvent::Error(err) => {
+                online.status = format!("Connection error: {}", err);
+                if in_game {
+                    error!("{}", online.status);
+                    next_state.set(GameState::Menu);
+                }
+                continue;
+            }
+            WsEvent::Closed => {
+                // the server closes the connection once the match is finished
+                let finished = match online.pending.back() {
+                    Some((_, state)) => state.outcome.is_some(),
+                    None => match_state.as_ref().is_some_and(|m| m.is_finished()),
+                };
+                if in_game && !finished {
+                    error!("Connection to the server closed");
+                    next_state.set(GameState::Menu);
+                }
+                online.status = "Connection closed".to_owned();
+                continue;
+            }
+        };
+        match message {
+            ServerMessage::MatchStarted {
+                you,
+                opponent,
+                state,
+            } => {
+                let me = PlayerInformation {
+                    deck: DeckAsset(state.deck.0.clone()),
+                    ..player.clone()
+                };
+                let enemy = PlayerInformation {
+                    name: opponent.name,
+                    avatar_id: opponent.avatar_id,
+                    deck: DeckAsset(state.enemy_deck.0.clone()),
+                };
+                // match setup always puts `PlayerInformation` on the first seat
+                let (first, second) = match you {
+                    PlayerNumber::First => (me, enemy),
+                    PlayerNumber::Second => (enemy, me),
+                };
+                *player = first;
+                commands.insert_resource(OpponentInformation(second));
+                // the server may have other numbers for the rules with the same name
+                commands.insert_resource(state.rules.clone());
+                online.me = you;
+                online.start = Some(state);
+                next_state.set(GameState::Game);
+            }
+            ServerMessage::StateSync { events, state } => online.pending.push_back((events, state)),
+            ServerMessage::Error(ProtocolError::Rule(err)) => {
+                warn!("Server rejected the action: {}", err);
+                online.rejected = true;
+            }
+            ServerMessage::Error(err) => {
+                online.status = err.to_string();
+                if in_game {
+                    error!("{}", err);
+                    next_state.set(GameState::Menu);
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the match created locally with the one received from the server.
+fn prepare_online_match(
+    mut commands: Commands,
+    mut online: ResMut<OnlineMatch>,
+    mut players: Query<(Entity, PlayerStateQuery)>,
+    mut rng: ResMut<SeededRng>,
+) -> Result {
+    let start = online.start.take().ok_or("Online match was not started")?;
+    let match_state = start.view().determinize(&mut **rng);
+    for (e, mut player) in players.iter_mut() {
+        player.sync(&match_state);
+        let mut entity = commands.entity(e);
+        entity.remove::<(HumanPlayer, AiPlayer)>();
+        if *player.nr == online.me {
+            entity.insert(HumanPlayer);
+        } else {
+            entity.insert(RemotePlayer);
+        }
+    }
+    commands.insert_resource(match_state);
+    Ok(())
+}
+
+fn send_concede(
+    mut concede: EventReader<OnlineConcede>,
+    connection: Option<NonSendMut<ServerConnection>>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    for _ in concede.read() {
+        send(&mut connection, &ClientMessage::Concede);
+    }
+}
+
+fn await_remote_action(
+    actor: Query<(), (With<CurrentActorToken>, With<RemotePlayer>)>,
+    online: Res<OnlineMatch>,
+    mut next_state: ResMut<NextState<GameTurnSteps>>,
+) {
+    if !actor.is_empty() && !online.pending.is_empty() {
+        next_state.set(GameTurnSteps::PerformAction);
+    }
+}
+
+fn send_action(
+    mut commands: Commands,
+    actions: Query<(Entity, &ActionTaken), With<HumanPlayer>>,
+    connection: Option<NonSendMut<ServerConnection>>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    for (e, action) in actions.iter() {
+        send(&mut connection, &ClientMessage::Action(action.clone()));
+        commands.entity(e).remove::<ActionTaken>();
+    }
+}
+
+/// Applies the next update from the server, the hidden information is sampled
+/// the same way the AI does it, only the visible part has to match.
+fn apply_update(
+    mut commands: Commands,
+    mut online: ResMut<OnlineMatch>,
+    actor: Query<(Entity, &PlayerNumber), With<CurrentActorToken>>,
+    mut players: Query<PlayerStateQuery>,
+    mut rng: ResMut<SeededRng>,
+    mut updates: EventWriter<MatchUpdate>,
+    mut next_state: ResMut<NextState<GameTurnSteps>>,
+) {
+    let Ok((e, nr)) = actor.single() else {
+        return;
+    };
+    if online.rejected {
+        // keep the turn, so the action can be picked again
+        online.rejected = false;
+        commands.entity(e).insert(ExtraTurn);
+        next_state.set(GameTurnSteps::SearchForAgents);
+        return;
+    }
+    let Some((events, state)) = online.pending.pop_front() else {
+        return;
+    };
+    let match_state = state.view().determinize(&mut **rng);
+    for mut player in players.iter_mut() {
+        player.sync(&match_state);
+    }
+    for event in events {
+        if matches!(event, MatchEvent::TurnStarted { player, .. } if player == *nr) {
+            commands.entity(e).insert(ExtraTurn);
+        }
+        updates.write(MatchUpdate(event));
+    }
+    commands.insert_resource(match_state);
+    next_state.set(GameTurnSteps::SearchForAgents);
+}
+
+fn back_to_menu(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Menu);
+}
+
+fn update_lobby_status(
+    online: Option<Res<OnlineMatch>>,
+    mut q: Query<&mut Text, With<LobbyStatus>>,
+) {
+    let Some(online) = online.filter(|o| o.is_changed()) else {
+        return;
+    };
+    for mut text in q.iter_mut() {
+        text.0 = online.status.clone();
+    }
+}
+
+fn setup_lobby_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(30.0);
+    commands
+        .spawn(super::root_node())
+        .insert((StateScoped(GameState::Lobby), Name::new("lobby-root")))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("Online match"), header_style));
+                    parent.spawn((
+                        Text::default(),
+                        TextFont::from_font(asset_server.load(consts::REGULAR_FONT))
+                            .with_font_size(20.0),
+                        TextColor(Srgba::hex("fcfd9e").unwrap().into()),
+                        LobbyStatus,
+                    ));
+                    let id = parent
+                        .spawn(super::button(&asset_server))
+                        .with_child((
+                            Text::new("Back"),
+                            button_style,
+                            TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
+                        ))
+                        .id();
+                    parent.spawn((
+                        Observer::new(back_to_menu).with_entity(id),
+                        Name::new("Back observer"),
+                    ));
+                });
+        });
+}
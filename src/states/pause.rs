@@ -0,0 +1,289 @@
+use super::consts;
+use super::game::{GameMode, GameObject, HumanPlayer, MatchUpdate, PlayerNumber};
+use super::game_states::GameState;
+use super::online::OnlineConcede;
+use crate::base_systems::turn_based::GameTurnSteps;
+use crate::helpers::button::ButtonReleased;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use game_core::data::ruleset::Ruleset;
+use game_core::r#match::MatchState;
+
+/// Volume levels picked in turn by the settings button
+const VOLUME_STEPS: [f32; 5] = [1.0, 0.75, 0.5, 0.25, 0.0];
+
+/// Shows the pause overlay when Escape is pressed during the match.
+pub struct PausePlugin;
+
+/// Present while the match is paused, AI and turn timers wait until it is removed.
+#[derive(Resource, Debug, Default)]
+pub struct GamePaused;
+
+#[derive(Component)]
+#[require(GameObject)]
+struct PauseOverlay;
+
+/// Page of the pause overlay, only one of them is visible at once.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum PausePage {
+    Main,
+    Rules,
+    Settings,
+}
+
+#[derive(Component)]
+enum PauseButton {
+    Resume,
+    Open(PausePage),
+    Volume,
+    Concede,
+    Quit,
+}
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            toggle_pause
+                .run_if(in_state(GameState::Game))
+                .run_if(not(super::game::game_ended_condition)),
+        )
+        .add_systems(
+            Update,
+            (
+                setup_ui.run_if(resource_added::<GamePaused>),
+                remove_overlay.run_if(resource_removed::<GamePaused>),
+            )
+                .after(toggle_pause),
+        )
+        .add_systems(OnExit(GameState::Game), resume);
+    }
+}
+
+fn resume(mut commands: Commands) {
+    commands.remove_resource::<GamePaused>();
+}
+
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    paused: Option<Res<GamePaused>>,
+    mut commands: Commands,
+) {
+    if !keys.just_released(KeyCode::Escape) {
+        return;
+    }
+    if paused.is_some() {
+        commands.remove_resource::<GamePaused>();
+    } else {
+        commands.insert_resource(GamePaused);
+    }
+}
+
+fn remove_overlay(mut commands: Commands, overlay: Query<Entity, With<PauseOverlay>>) {
+    for e in overlay.iter() {
+        commands.entity(e).despawn();
+    }
+}
+
+fn volume_label(volume: &GlobalVolume) -> String {
+    format!("Volume: {:.0}%", volume.volume.to_linear() * 100.0)
+}
+
+fn button_system(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&PauseButton>,
+    mut pages: Query<(&PausePage, &mut Node)>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    let Ok(button) = buttons.get(trigger.target()) else {
+        return;
+    };
+    match *button {
+        PauseButton::Resume => commands.remove_resource::<GamePaused>(),
+        PauseButton::Open(page) => {
+            for (p, mut node) in pages.iter_mut() {
+                node.display = if *p == page {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+            }
+        }
+        // the match is saved every turn, so it can be continued from the menu
+        PauseButton::Quit => next_state.set(GameState::Menu),
+        PauseButton::Volume | PauseButton::Concede => {}
+    }
+}
+
+fn change_volume(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&PauseButton>,
+    mut volume: ResMut<GlobalVolume>,
+    mut sinks: Query<(&mut AudioSink, &PlaybackSettings)>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok(PauseButton::Volume) = buttons.get(trigger.target()) else {
+        return;
+    };
+    let current = volume.volume.to_linear();
+    let i = VOLUME_STEPS
+        .iter()
+        .position(|v| (v - current).abs() < 0.01)
+        .map_or(0, |i| (i + 1) % VOLUME_STEPS.len());
+    volume.volume = Volume::Linear(VOLUME_STEPS[i]);
+    // the global volume is only applied when a sound starts playing
+    for (mut sink, settings) in sinks.iter_mut() {
+        sink.set_volume(settings.volume * volume.volume);
+    }
+    for child in children.iter_descendants(trigger.target()) {
+        if let Ok(mut text) = texts.get_mut(child) {
+            text.0 = volume_label(&volume);
+        }
+    }
+}
+
+fn concede(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&PauseButton>,
+    mut match_state: ResMut<MatchState>,
+    humans: Query<&PlayerNumber, With<HumanPlayer>>,
+    mode: Res<GameMode>,
+    mut updates: EventWriter<MatchUpdate>,
+    mut online_concede: EventWriter<OnlineConcede>,
+    mut next_turn: ResMut<NextState<GameTurnSteps>>,
+    mut commands: Commands,
+) {
+    let Ok(PauseButton::Concede) = buttons.get(trigger.target()) else {
+        return;
+    };
+    // in hot seat the player that has the turn gives up
+    let Some(player) = humans
+        .iter()
+        .find(|nr| **nr == match_state.current)
+        .or_else(|| humans.iter().next())
+        .copied()
+    else {
+        return;
+    };
+    match match_state.concede(player) {
+        Ok(events) => {
+            if *mode == GameMode::Online {
+                online_concede.write(OnlineConcede);
+            }
+            updates.write_batch(events.into_iter().map(MatchUpdate));
+            next_turn.set(GameTurnSteps::SearchForAgents);
+        }
+        Err(err) => warn!("Cannot concede: {}", err),
+    }
+    commands.remove_resource::<GamePaused>();
+}
+
+fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    rules: Res<Ruleset>,
+    volume: Res<GlobalVolume>,
+    humans: Query<(), With<HumanPlayer>>,
+) {
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+    let can_concede = !humans.is_empty();
+
+    let page = |kind: PausePage| {
+        (
+            Node {
+                display: if kind == PausePage::Main {
+                    Display::Flex
+                } else {
+                    Display::None
+                },
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+            kind,
+        )
+    };
+    let button = |parent: &mut ChildSpawnerCommands, text: String, kind: PauseButton| {
+        parent
+            .spawn((super::button(&asset_server), kind))
+            .with_child((Text::new(text), button_style.clone(), clr));
+    };
+
+    commands.spawn((Observer::new(button_system), PauseOverlay));
+    commands.spawn((Observer::new(change_volume), PauseOverlay));
+    commands.spawn((Observer::new(concede), PauseOverlay));
+    commands
+        .spawn((
+            super::root_node(),
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            GlobalZIndex(10),
+            PauseOverlay,
+            Name::new("pause-overlay"),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(page(PausePage::Main)).with_children(|parent| {
+                        parent.spawn((Text::new("Paused"), header_style.clone()));
+                        button(parent, "Resume".to_owned(), PauseButton::Resume);
+                        button(
+                            parent,
+                            "Rules".to_owned(),
+                            PauseButton::Open(PausePage::Rules),
+                        );
+                        button(
+                            parent,
+                            "Settings".to_owned(),
+                            PauseButton::Open(PausePage::Settings),
+                        );
+                        if can_concede {
+                            button(parent, "Concede".to_owned(), PauseButton::Concede);
+                        }
+                        button(parent, "Quit to menu".to_owned(), PauseButton::Quit);
+                    });
+                    parent
+                        .spawn(page(PausePage::Rules))
+                        .with_children(|parent| {
+                            parent.spawn((Text::new("Rules"), header_style.clone()));
+                            parent.spawn((
+                                TextFont::from_font(asset_server.load(consts::REGULAR_FONT))
+                                    .with_font_size(25.0),
+                                TextColor(Srgba::hex("fcfd9e").unwrap().into()),
+                                Text::new(consts::help_text(rules.max_tower_hp)),
+                            ));
+                            button(
+                                parent,
+                                "Back".to_owned(),
+                                PauseButton::Open(PausePage::Main),
+                            );
+                        });
+                    parent
+                        .spawn(page(PausePage::Settings))
+                        .with_children(|parent| {
+                            parent.spawn((Text::new("Settings"), header_style.clone()));
+                            button(parent, volume_label(&volume), PauseButton::Volume);
+                            button(
+                                parent,
+                                "Back".to_owned(),
+                                PauseButton::Open(PausePage::Main),
+                            );
+                        });
+                });
+        });
+}
@@ -1,12 +1,13 @@
 use super::consts;
 use super::game_states::GameState;
-use crate::data::profile::{Profile, ProfileProvider};
+use crate::components::ObserverExtension;
+use crate::data::profile::{ActiveProfile, AvatarsAsset, Profile, ProfileProvider};
+use crate::data::saved_match::SavedMatchProvider;
 use crate::helpers::button::ButtonReleased;
 use bevy::prelude::*;
 use bevy_pkv::PkvStore;
 use bevy_simple_text_input::{TextInput, TextInputPlugin, TextInputSettings, TextInputValue};
 use bevy_tweening::{Animator, Tween, lens::TransformScaleLens};
-use rand::Rng;
 use std::time::Duration;
 
 pub struct ProfileSelectionPlugin;
@@ -16,8 +17,25 @@ pub enum ProfileEditButton {
     NextAvatar,
     PreviousAvatar,
     Save,
+    Back,
 }
 
+#[derive(Component)]
+enum ProfileListButton {
+    Pick(u32),
+    Rename(u32),
+    Delete(u32),
+    New,
+}
+
+/// Row of the profiles list, removed together with its profile.
+#[derive(Component)]
+struct ProfileRow(u32);
+
+/// Profile changed in the edit screen, `None` creates a new one.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct EditedProfile(pub Option<u32>);
+
 #[derive(Component, Reflect)]
 pub struct AvatarDisplay {
     id: i32,
@@ -26,6 +44,9 @@ pub struct AvatarDisplay {
 impl Plugin for ProfileSelectionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(TextInputPlugin)
+            .init_resource::<EditedProfile>()
+            .add_systems(Startup, migrate_profiles)
+            .add_systems(OnEnter(GameState::ProfileSelection), setup_list_ui)
             .add_systems(OnEnter(GameState::ProfileEdit), setup_ui)
             .add_systems(
                 Update,
@@ -34,6 +55,10 @@ impl Plugin for ProfileSelectionPlugin {
     }
 }
 
+fn migrate_profiles(mut pkv: ResMut<PkvStore>) {
+    pkv.migrate_profiles();
+}
+
 fn avatar_update(
     mut avatar_query: Query<(&AvatarDisplay, &mut ImageNode), Changed<AvatarDisplay>>,
     asset_server: Res<AssetServer>,
@@ -48,9 +73,8 @@ fn button_system(
     trigger: Trigger<ButtonReleased>,
     interaction_query: Query<&ProfileEditButton>,
     mut next_state: ResMut<NextState<GameState>>,
-    changed_value: Query<&TextInputValue>,
     mut avatar_query: Query<&mut AvatarDisplay>,
-    mut pkv: ResMut<PkvStore>,
+    avatars: Res<Assets<AvatarsAsset>>,
 ) {
     let Ok(button_type) = interaction_query.get(trigger.target()) else {
         return;
@@ -58,57 +82,215 @@ fn button_system(
 
     match *button_type {
         ProfileEditButton::NextAvatar => {
+            let Some((_, avatars)) = avatars.iter().next() else {
+                return;
+            };
             for mut avatar in &mut avatar_query {
-                avatar.id = if avatar.id + 1 > consts::AVATARS_AMOUNT {
-                    1
-                } else {
-                    avatar.id + 1
-                };
+                avatar.id = avatars.next(avatar.id);
             }
         }
         ProfileEditButton::PreviousAvatar => {
+            let Some((_, avatars)) = avatars.iter().next() else {
+                return;
+            };
             for mut avatar in &mut avatar_query {
-                avatar.id = if avatar.id - 1 < 1 {
-                    consts::AVATARS_AMOUNT
-                } else {
-                    avatar.id - 1
-                };
+                avatar.id = avatars.previous(avatar.id);
             }
         }
-        ProfileEditButton::Save => {
-            let Ok(avatar) = avatar_query.single() else {
-                return;
-            };
-            let Ok(profile_name) = changed_value.single() else {
-                return;
-            };
-            let mut profile = pkv.as_ref().get_profile().unwrap_or_default();
+        // handled by `save_profile`, observing only the save button
+        ProfileEditButton::Save => {}
+        ProfileEditButton::Back => next_state.set(GameState::ProfileSelection),
+    }
+}
+
+fn save_profile(
+    _: Trigger<ButtonReleased>,
+    mut next_state: ResMut<NextState<GameState>>,
+    changed_value: Query<&TextInputValue>,
+    avatar_query: Query<&AvatarDisplay>,
+    edited: Res<EditedProfile>,
+    mut pkv: ResMut<PkvStore>,
+    mut commands: Commands,
+) {
+    let Ok(avatar) = avatar_query.single() else {
+        return;
+    };
+    let Ok(profile_name) = changed_value.single() else {
+        return;
+    };
+    let mut profiles = pkv.get_profiles();
+    let id = match edited.0.and_then(|id| profiles.get_mut(id)) {
+        Some(profile) => {
             profile.avatar_id = avatar.id;
             profile.name.clone_from(&profile_name.0);
-            profile.save_profile(&mut pkv);
-            next_state.set(GameState::Menu);
+            profile.id
         }
-    }
+        None => profiles.add(Profile {
+            avatar_id: avatar.id,
+            name: profile_name.0.clone(),
+            ..default()
+        }),
+    };
+    profiles.selected = Some(id);
+    pkv.save_profiles(&profiles);
+    commands.insert_resource(ActiveProfile(id));
+    next_state.set(GameState::Menu);
 }
 
-fn get_avatar_id(pkv: &PkvStore) -> i32 {
-    match pkv.get_profile() {
-        Some(profile) => profile.avatar_id,
-        None => {
-            let mut rng = rand::thread_rng();
-            rng.gen_range(1..consts::AVATARS_AMOUNT)
+fn list_button_system(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&ProfileListButton>,
+    rows: Query<(Entity, &ProfileRow)>,
+    active: Option<Res<ActiveProfile>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut pkv: ResMut<PkvStore>,
+    mut commands: Commands,
+) {
+    let Ok(button) = buttons.get(trigger.target()) else {
+        return;
+    };
+    match *button {
+        ProfileListButton::Pick(id) => {
+            let mut profiles = pkv.get_profiles();
+            profiles.selected = Some(id);
+            pkv.save_profiles(&profiles);
+            commands.insert_resource(ActiveProfile(id));
+            next_state.set(GameState::Menu);
+        }
+        ProfileListButton::Rename(id) => {
+            commands.insert_resource(EditedProfile(Some(id)));
+            next_state.set(GameState::ProfileEdit);
+        }
+        ProfileListButton::Delete(id) => {
+            let mut profiles = pkv.get_profiles();
+            profiles.remove(id);
+            pkv.save_profiles(&profiles);
+            pkv.remove_saved_match(id);
+            if active.is_some_and(|a| a.0 == id) {
+                commands.remove_resource::<ActiveProfile>();
+            }
+            for (entity, _) in rows.iter().filter(|(_, row)| row.0 == id) {
+                commands.entity(entity).despawn();
+            }
+        }
+        ProfileListButton::New => {
+            commands.insert_resource(EditedProfile(None));
+            next_state.set(GameState::ProfileEdit);
         }
     }
 }
 
-fn get_user_name(pkv: &PkvStore) -> String {
-    match pkv.get_profile() {
-        Some(profile) => profile.name.clone(),
-        None => "Wojmir".to_owned(),
+fn setup_list_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pkv: Res<PkvStore>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let profiles = pkv.get_profiles();
+    if profiles.list.is_empty() {
+        commands.insert_resource(EditedProfile(None));
+        next_state.set(GameState::ProfileEdit);
+        return;
     }
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+
+    commands
+        .spawn(Observer::new(list_button_system))
+        .insert(StateScoped(GameState::ProfileSelection));
+    commands
+        .spawn(super::root_node())
+        .insert((
+            StateScoped(GameState::ProfileSelection),
+            Name::new("profiles-root"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("Who is playing?"), header_style));
+                    for profile in &profiles.list {
+                        let label = if profiles.selected == Some(profile.id) {
+                            format!("{} (last played)", profile.name)
+                        } else {
+                            profile.name.clone()
+                        };
+                        parent
+                            .spawn((
+                                Node {
+                                    align_items: AlignItems::Center,
+                                    column_gap: Val::Px(10.0),
+                                    ..default()
+                                },
+                                ProfileRow(profile.id),
+                                Name::new(format!("profile:{}", profile.name)),
+                            ))
+                            .with_children(|row| {
+                                row.spawn((
+                                    ImageNode::new(asset_server.load(profile.get_avatar_path())),
+                                    Node {
+                                        width: Val::Px(50.0),
+                                        height: Val::Px(50.0),
+                                        ..default()
+                                    },
+                                ));
+                                for (text, button) in [
+                                    (label, ProfileListButton::Pick(profile.id)),
+                                    ("Rename".to_owned(), ProfileListButton::Rename(profile.id)),
+                                    ("Delete".to_owned(), ProfileListButton::Delete(profile.id)),
+                                ] {
+                                    row.spawn((super::button(&asset_server), button))
+                                        .with_child((Text::new(text), button_style.clone(), clr));
+                                }
+                            });
+                    }
+                    parent
+                        .spawn((super::button(&asset_server), ProfileListButton::New))
+                        .with_child((Text::new("New Profile"), button_style.clone(), clr));
+                });
+        });
 }
 
-fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<PkvStore>) {
+fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pkv: Res<PkvStore>,
+    edited: Res<EditedProfile>,
+    avatars: Res<Assets<AvatarsAsset>>,
+) -> Result {
+    let (_, avatars) = avatars.iter().next().ok_or("Missing avatars asset")?;
+    let profiles = pkv.get_profiles();
+    let profile = edited.0.and_then(|id| profiles.get(id));
+    let (title, user_name, avatar_id) = match profile {
+        Some(profile) => ("Edit profile", profile.name.clone(), profile.avatar_id),
+        None => (
+            "New profile",
+            Profile::default().name,
+            avatars.random(&mut rand::thread_rng()),
+        ),
+    };
     commands
         .spawn(Observer::new(button_system))
         .insert(StateScoped(GameState::ProfileEdit));
@@ -151,9 +333,7 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<Pkv
                     // Class::new("popup_window"),
                 ))
                 .with_children(|parent| {
-                    parent
-                        .spawn(Node::default())
-                        .with_child(Text::new("Edit profile"));
+                    parent.spawn(Node::default()).with_child(Text::new(title));
 
                     let init_scale = Vec3::splat(0.01);
                     let tween_scale = Tween::new(
@@ -174,7 +354,7 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<Pkv
                             ..default()
                         },
                         TextInput,
-                        TextInputValue(get_user_name(pkv.as_ref())),
+                        TextInputValue(user_name),
                         TextColor(Srgba::hex("fcfd9e").unwrap().into()),
                         TextInputSettings {
                             retain_on_submit: true,
@@ -192,7 +372,6 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<Pkv
                            //     }),
                     ));
 
-                    let avatar_id = get_avatar_id(pkv.as_ref());
                     let avatar_path = Profile::format_avatar_path(avatar_id);
                     parent
                         .spawn((
@@ -301,7 +480,22 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<Pkv
                                 ..default()
                             },
                             TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
-                        ));
+                        ))
+                        .observe_in_child(save_profile);
+                    if !profiles.list.is_empty() {
+                        parent
+                            .spawn((super::button(&asset_server), ProfileEditButton::Back))
+                            .with_child((
+                                Text::new("Back"),
+                                TextFont {
+                                    font: asset_server.load(consts::LABEL_FONT),
+                                    font_size: 30.0,
+                                    ..default()
+                                },
+                                TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
+                            ));
+                    }
                 });
         });
+    Ok(())
 }
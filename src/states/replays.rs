@@ -0,0 +1,351 @@
+use super::consts;
+use super::game::{
+    ActionTaken, GameMode, GameObject, HumanPlayer, MatchUpdate, OpponentInformation,
+    PlayerInformation,
+};
+use super::game_states::GameState;
+use crate::base_systems::turn_based::{CurrentActorToken, GameTurnSteps};
+use crate::components::card_display::DraggableCard;
+use crate::data::replay::{Replay, ReplayProvider};
+use crate::data::saved_match::ResumedMatch;
+use crate::data::seed::MatchSeed;
+use crate::helpers::button::ButtonReleased;
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::ai::AiPlayer;
+use game_core::data::player::PlayerNumber;
+use game_core::data::ruleset::Ruleset;
+use game_core::r#match::{MatchEvent, MatchState};
+
+pub struct ReplaysPlugin;
+
+/// Replay of the match that is being played right now.
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct ReplayRecorder(pub Replay);
+
+/// Replay that is being watched, `next` is the index of the next action to perform.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub next: usize,
+    pub step_requested: bool,
+}
+
+/// Marks players driven by the replay instead of a human or AI.
+#[derive(Component, Debug, Default)]
+pub struct ReplayActor;
+
+#[derive(Resource, Debug, Default, Deref)]
+struct StoredReplays(Vec<Replay>);
+
+#[derive(Component)]
+enum ReplaysButton {
+    Play(usize),
+    Back,
+}
+
+#[derive(Component)]
+#[require(GameObject)]
+struct PlaybackStatus;
+
+impl Plugin for ReplaysPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Replays), setup_ui)
+            .add_systems(
+                OnEnter(GameState::Game),
+                (
+                    // online matches are hosted with a seed only known to the server
+                    start_recording.run_if(
+                        not(resource_exists::<ReplayPlayback>)
+                            .and(not(resource_equals(GameMode::Online))),
+                    ),
+                    (prepare_playback, setup_playback_ui).run_if(resource_exists::<ReplayPlayback>),
+                )
+                    .after(super::game::init_players),
+            )
+            .add_systems(
+                Update,
+                record_actions
+                    .run_if(resource_exists::<ReplayRecorder>)
+                    .after(super::game::perform_action),
+            )
+            .add_systems(
+                Update,
+                (
+                    request_step,
+                    lock_cards,
+                    perform_step.run_if(in_state(GameTurnSteps::ActionSelection)),
+                    update_playback_status,
+                )
+                    .run_if(resource_exists::<ReplayPlayback>.and(in_state(GameState::Game))),
+            )
+            .add_systems(OnExit(GameState::Game), stop_replays);
+    }
+}
+
+fn start_recording(
+    mut commands: Commands,
+    seed: Res<MatchSeed>,
+    player: Res<PlayerInformation>,
+    opponent: Res<OpponentInformation>,
+    rules: Res<Ruleset>,
+    resumed: Option<Res<ResumedMatch>>,
+) {
+    // resumed matches keep recording the saved replay
+    let replay = resumed
+        .and_then(|r| r.replay.clone())
+        .unwrap_or_else(|| Replay::new(seed.seed, &player, &opponent, &rules));
+    commands.insert_resource(ReplayRecorder(replay));
+}
+
+fn record_actions(
+    mut events: EventReader<MatchUpdate>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    for event in events.read() {
+        match &**event {
+            MatchEvent::CardUsed { card, .. } => recorder
+                .actions
+                .push(ActionTaken::UseCard { card: card.clone() }),
+            MatchEvent::CardDropped { card, .. } => recorder
+                .actions
+                .push(ActionTaken::DropCard { card: card.clone() }),
+            MatchEvent::MatchEnded(outcome) => {
+                recorder.finish(*outcome);
+                pkv.save_replay(&recorder);
+                info!("Replay saved, {} actions", recorder.actions.len());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn stop_replays(mut commands: Commands) {
+    commands.remove_resource::<ReplayRecorder>();
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+fn prepare_playback(mut commands: Commands, players: Query<Entity, With<PlayerNumber>>) {
+    for e in players.iter() {
+        commands
+            .entity(e)
+            .remove::<(HumanPlayer, AiPlayer)>()
+            .insert(ReplayActor);
+    }
+}
+
+/// Cards of the replayed players are only displayed, never dragged.
+fn lock_cards(mut commands: Commands, q: Query<Entity, Added<DraggableCard>>) {
+    for e in q.iter() {
+        commands.entity(e).insert(Pickable::IGNORE);
+    }
+}
+
+fn request_step(keys: Res<ButtonInput<KeyCode>>, mut playback: ResMut<ReplayPlayback>) {
+    if keys.any_just_released([KeyCode::Space, KeyCode::ArrowRight]) {
+        playback.step_requested = true;
+    }
+}
+
+fn next_turn_clicked(_: Trigger<ButtonReleased>, mut playback: ResMut<ReplayPlayback>) {
+    playback.step_requested = true;
+}
+
+fn perform_step(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    actor: Query<Entity, (With<CurrentActorToken>, Without<ActionTaken>)>,
+    mut match_state: ResMut<MatchState>,
+    mut updates: EventWriter<MatchUpdate>,
+    mut next_turn: ResMut<NextState<GameTurnSteps>>,
+) {
+    if !playback.step_requested {
+        return;
+    }
+    let Ok(e) = actor.single() else {
+        return;
+    };
+    playback.step_requested = false;
+    if let Some(action) = playback.replay.actions.get(playback.next).cloned() {
+        playback.next += 1;
+        commands.entity(e).insert(action);
+        return;
+    }
+    // the concession is the last step, it isn't an action of the current player
+    let Some(player) = playback.replay.conceded else {
+        return;
+    };
+    if playback.next == playback.replay.steps() {
+        return;
+    }
+    playback.next += 1;
+    match match_state.concede(player) {
+        Ok(events) => {
+            updates.write_batch(events.into_iter().map(MatchUpdate));
+            next_turn.set(GameTurnSteps::SearchForAgents);
+        }
+        Err(err) => warn!("Cannot replay the concession: {}", err),
+    }
+}
+
+fn update_playback_status(
+    playback: Res<ReplayPlayback>,
+    match_state: Res<MatchState>,
+    mut q: Query<&mut Text, With<PlaybackStatus>>,
+) {
+    if !playback.is_changed() && !match_state.is_changed() {
+        return;
+    }
+    for mut text in q.iter_mut() {
+        text.0 = format!("Replay: turn {}/{}", playback.next, playback.replay.steps());
+    }
+}
+
+fn setup_playback_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(30.0);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(15.0),
+                right: Val::Px(15.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Val::Px(5.0),
+                ..default()
+            },
+            GameObject,
+            Name::new("Replay controls"),
+        ))
+        .with_children(|p| {
+            p.spawn((Text::default(), font.clone(), PlaybackStatus));
+            p.spawn((
+                TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(16.0),
+                Text::new("Space - next turn"),
+            ));
+            let id = p
+                .spawn(super::button(&asset_server))
+                .with_child((
+                    Text::new("Next turn"),
+                    font,
+                    TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
+                ))
+                .id();
+            p.spawn((
+                Observer::new(next_turn_clicked).with_entity(id),
+                Name::new("Next turn observer"),
+            ));
+        });
+}
+
+fn button_system(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&ReplaysButton>,
+    replays: Res<StoredReplays>,
+    mut seed: ResMut<MatchSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    let Ok(button) = buttons.get(trigger.target()) else {
+        return;
+    };
+    match *button {
+        ReplaysButton::Play(i) => {
+            let Some(replay) = replays.get(i) else {
+                return;
+            };
+            seed.seed = replay.seed;
+            commands.insert_resource(replay.player.clone());
+            commands.insert_resource(OpponentInformation(replay.opponent.clone()));
+            commands.insert_resource(replay.rules.clone());
+            // the mode of the last played match would gate out the playback or go online
+            commands.insert_resource(GameMode::VsAi);
+            commands.insert_resource(ReplayPlayback {
+                replay: replay.clone(),
+                next: 0,
+                step_requested: false,
+            });
+            next_state.set(GameState::Game);
+        }
+        ReplaysButton::Back => next_state.set(GameState::Menu),
+    }
+}
+
+fn replay_label(replay: &Replay) -> String {
+    let result = match replay.outcome {
+        Some(outcome) if outcome.winner == PlayerNumber::First => "won",
+        Some(_) => "lost",
+        None => "unfinished",
+    };
+    format!(
+        "{} vs {} - {} in {} actions",
+        replay.player.name,
+        replay.opponent.name,
+        result,
+        replay.actions.len()
+    )
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<PkvStore>) {
+    let replays = StoredReplays(pkv.get_replays());
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+
+    commands
+        .spawn(Observer::new(button_system))
+        .insert(StateScoped(GameState::Replays));
+    commands
+        .spawn(super::root_node())
+        .insert((StateScoped(GameState::Replays), Name::new("replays-root")))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("Replays"), header_style));
+                    if replays.is_empty() {
+                        parent.spawn((
+                            Text::new("Finish a match to record its replay"),
+                            TextFont::from_font(asset_server.load(consts::REGULAR_FONT))
+                                .with_font_size(20.0),
+                            TextColor(Srgba::hex("fcfd9e").unwrap().into()),
+                        ));
+                    }
+                    for (i, replay) in replays.iter().enumerate() {
+                        parent
+                            .spawn((super::button(&asset_server), ReplaysButton::Play(i)))
+                            .with_child((
+                                Text::new(replay_label(replay)),
+                                button_style.clone(),
+                                clr,
+                            ));
+                    }
+                    parent
+                        .spawn((super::button(&asset_server), ReplaysButton::Back))
+                        .with_child((Text::new("Back"), button_style.clone(), clr));
+                });
+        });
+    commands.insert_resource(replays);
+}
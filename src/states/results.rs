@@ -0,0 +1,378 @@
+use super::consts;
+use super::game::{GameMode, HumanPlayer, MatchUpdate, PlayerDetailsInfo, PlayerNumber};
+use super::game_states::GameState;
+use super::menu::{MatchContent, roll_match};
+use super::replays::ReplayPlayback;
+use crate::base_systems::turn_based::GameTurnSteps;
+use crate::data::profile::Profile;
+use crate::data::seed::MatchSeed;
+use crate::helpers::button::ButtonReleased;
+use bevy::prelude::*;
+use game_core::data::player::PlayerHealth;
+use game_core::data::resource::ResourceType;
+use game_core::data::supply::PlayerSupply;
+use game_core::r#match::{MatchEvent, MatchOutcome, MatchState, WinReason};
+
+const GRAPH_WIDTH: f32 = 480.0;
+const GRAPH_HEIGHT: f32 = 160.0;
+const PLAYER_COLORS: [&str; 2] = ["7A444A", "4d6e74"];
+
+pub struct ResultsPlugin;
+
+/// Health of both players at the start of every turn of the match being played.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct HealthHistory(pub Vec<[PlayerHealth; 2]>);
+
+#[derive(Debug, Clone)]
+pub struct PlayerResults {
+    pub details: PlayerDetailsInfo,
+    pub health: PlayerHealth,
+    pub supply: PlayerSupply,
+}
+
+/// Summary of the finished match shown on the results screen.
+#[derive(Resource, Debug, Clone)]
+pub struct MatchResults {
+    pub outcome: MatchOutcome,
+    /// Player the results are shown to, `None` when both or none of the players are humans
+    pub viewer: Option<PlayerNumber>,
+    pub players: [PlayerResults; 2],
+    pub health: Vec<[PlayerHealth; 2]>,
+    /// Online matches and replays can't be played again from here
+    pub can_rematch: bool,
+}
+
+#[derive(Component)]
+enum ResultsButton {
+    Rematch,
+    NewOpponent,
+    Menu,
+}
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HealthHistory>()
+            .add_systems(OnEnter(GameState::Game), reset_history)
+            .add_systems(
+                Update,
+                record_health
+                    .run_if(in_state(GameState::Game).and(resource_exists::<MatchState>))
+                    .after(super::game::perform_action),
+            )
+            .add_systems(
+                OnEnter(GameTurnSteps::SearchForAgents),
+                prepare_results.run_if(super::game::game_ended_condition),
+            )
+            .add_systems(OnEnter(GameState::Results), setup_ui);
+    }
+}
+
+fn reset_history(mut history: ResMut<HealthHistory>) {
+    history.clear();
+}
+
+fn health_snapshot(match_state: &MatchState) -> [PlayerHealth; 2] {
+    PlayerNumber::ALL.map(|nr| match_state.player(nr).health)
+}
+
+fn record_health(
+    mut events: EventReader<MatchUpdate>,
+    match_state: Res<MatchState>,
+    mut history: ResMut<HealthHistory>,
+) {
+    if history.is_empty() {
+        history.push(health_snapshot(&match_state));
+    }
+    for event in events.read() {
+        if matches!(**event, MatchEvent::TurnStarted { .. }) {
+            history.push(health_snapshot(&match_state));
+        }
+    }
+}
+
+fn prepare_results(
+    mut commands: Commands,
+    match_state: Res<MatchState>,
+    history: Res<HealthHistory>,
+    players: Query<(&PlayerNumber, &PlayerDetailsInfo, Has<HumanPlayer>)>,
+    mode: Res<GameMode>,
+    playback: Option<Res<ReplayPlayback>>,
+) -> Result {
+    let outcome = match_state.outcome().ok_or("Match is not finished")?;
+    let mut health = history.0.clone();
+    let last = health_snapshot(&match_state);
+    if health.last() != Some(&last) {
+        health.push(last);
+    }
+    let mut humans = players.iter().filter(|(_, _, human)| *human);
+    let viewer = match (humans.next(), humans.next()) {
+        (Some((nr, _, _)), None) => Some(*nr),
+        _ => None,
+    };
+    let player_results = |nr: PlayerNumber| {
+        let details = players
+            .iter()
+            .find(|(n, _, _)| **n == nr)
+            .map(|(_, details, _)| details.clone())
+            .unwrap_or_default();
+        let state = match_state.player(nr);
+        PlayerResults {
+            details,
+            health: state.health,
+            supply: state.supply.clone(),
+        }
+    };
+    commands.insert_resource(MatchResults {
+        outcome,
+        viewer,
+        players: PlayerNumber::ALL.map(player_results),
+        health,
+        can_rematch: playback.is_none() && *mode != GameMode::Online,
+    });
+    Ok(())
+}
+
+fn button_system(
+    trigger: Trigger<ButtonReleased>,
+    buttons: Query<&ResultsButton>,
+    content: MatchContent,
+    mut seed: ResMut<MatchSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) -> Result {
+    let Ok(button) = buttons.get(trigger.target()) else {
+        return Ok(());
+    };
+    match *button {
+        // the player and the opponent information are kept from the last match
+        ResultsButton::Rematch => {
+            roll_match(&mut seed, &content, &mut commands, false)?;
+            next_state.set(GameState::Game);
+        }
+        ResultsButton::NewOpponent => {
+            roll_match(&mut seed, &content, &mut commands, true)?;
+            next_state.set(GameState::Game);
+        }
+        ResultsButton::Menu => next_state.set(GameState::Menu),
+    }
+    Ok(())
+}
+
+impl MatchResults {
+    fn winner(&self) -> &PlayerResults {
+        &self.players[self.outcome.winner.index()]
+    }
+
+    fn title(&self) -> String {
+        match self.viewer {
+            Some(nr) if nr == self.outcome.winner => "Victory!".to_owned(),
+            Some(_) => "Defeat".to_owned(),
+            None => format!("{} won", self.winner().details.name),
+        }
+    }
+
+    fn reason(&self) -> String {
+        let name = &self.winner().details.name;
+        match self.outcome.reason {
+            WinReason::TowerDestroyed => format!("{} destroyed the enemy tower", name),
+            WinReason::TowerCompleted => format!("{} completed the tower", name),
+            WinReason::Conceded => format!(
+                "{} conceded",
+                self.players[self.outcome.winner.other().index()]
+                    .details
+                    .name
+            ),
+        }
+    }
+}
+
+fn player_lines(player: &PlayerResults) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Tower: {}  Walls: {}",
+        player.health.tower_hp, player.health.walls_hp
+    )];
+    for resource in ResourceType::ALL {
+        let supply = player.supply.get(resource);
+        lines.push(format!(
+            "{}: {} (+{})",
+            resource, supply.amount, supply.production
+        ));
+    }
+    lines
+}
+
+/// Bars of the tower and the walls of both players for every turn.
+fn spawn_health_graph(parent: &mut ChildSpawnerCommands, health: &[[PlayerHealth; 2]]) {
+    let max = health
+        .iter()
+        .flatten()
+        .map(|h| h.tower_hp + h.walls_hp)
+        .max()
+        .unwrap_or(1)
+        .max(1) as f32;
+    let percent = |hp: i32| Val::Percent(hp.max(0) as f32 * 100.0 / max);
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(GRAPH_WIDTH),
+                height: Val::Px(GRAPH_HEIGHT),
+                align_items: AlignItems::FlexEnd,
+                column_gap: Val::Px(1.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Srgba::hex("1d2c1e").unwrap().into()),
+            Name::new("health-graph"),
+        ))
+        .with_children(|graph| {
+            for turn in health {
+                graph
+                    .spawn(Node {
+                        flex_grow: 1.0,
+                        height: Val::Percent(100.0),
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    })
+                    .with_children(|column| {
+                        for (hp, color) in turn.iter().zip(PLAYER_COLORS) {
+                            let color: Color = Srgba::hex(color).unwrap().into();
+                            column
+                                .spawn(Node {
+                                    flex_grow: 1.0,
+                                    height: Val::Percent(100.0),
+                                    flex_direction: FlexDirection::ColumnReverse,
+                                    ..default()
+                                })
+                                .with_children(|bar| {
+                                    bar.spawn((
+                                        Node {
+                                            height: percent(hp.tower_hp),
+                                            ..default()
+                                        },
+                                        BackgroundColor(color),
+                                    ));
+                                    bar.spawn((
+                                        Node {
+                                            height: percent(hp.walls_hp),
+                                            ..default()
+                                        },
+                                        BackgroundColor(color.with_alpha(0.5)),
+                                    ));
+                                });
+                        }
+                    });
+            }
+        });
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, results: Res<MatchResults>) {
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let name_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(30.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let text_style =
+        TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(20.0);
+    let text_clr = TextColor(Srgba::hex("fcfd9e").unwrap().into());
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+
+    commands
+        .spawn(Observer::new(button_system))
+        .insert(StateScoped(GameState::Results));
+    commands
+        .spawn(super::root_node())
+        .insert((StateScoped(GameState::Results), Name::new("results-root")))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new(results.title()), header_style));
+                    parent.spawn((Text::new(results.reason()), text_style.clone(), text_clr));
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(40.0),
+                            ..default()
+                        })
+                        .with_children(|sides| {
+                            for (player, color) in results.players.iter().zip(PLAYER_COLORS) {
+                                sides
+                                    .spawn(Node {
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::Center,
+                                        row_gap: Val::Px(5.0),
+                                        ..default()
+                                    })
+                                    .with_children(|side| {
+                                        side.spawn((
+                                            ImageNode::new(asset_server.load(
+                                                Profile::format_avatar_path(
+                                                    player.details.avatar_id,
+                                                ),
+                                            )),
+                                            Node {
+                                                width: Val::Px(80.0),
+                                                height: Val::Px(80.0),
+                                                border: UiRect::all(Val::Px(3.0)),
+                                                ..default()
+                                            },
+                                            BorderColor(Srgba::hex(color).unwrap().into()),
+                                        ));
+                                        side.spawn((
+                                            Text::new(player.details.name.clone()),
+                                            name_style.clone(),
+                                            text_clr,
+                                        ));
+                                        for line in player_lines(player) {
+                                            side.spawn((
+                                                Text::new(line),
+                                                text_style.clone(),
+                                                text_clr,
+                                            ));
+                                        }
+                                    });
+                            }
+                        });
+                    spawn_health_graph(parent, &results.health);
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        })
+                        .with_children(|buttons| {
+                            let mut entries = vec![("Menu", ResultsButton::Menu)];
+                            if results.can_rematch {
+                                entries.insert(0, ("Rematch", ResultsButton::Rematch));
+                            }
+                            // hot seat opponents are not picked at random
+                            if results.can_rematch && results.viewer.is_some() {
+                                entries.insert(1, ("New opponent", ResultsButton::NewOpponent));
+                            }
+                            for (text, button) in entries {
+                                buttons
+                                    .spawn((super::button(&asset_server), button))
+                                    .with_child((Text::new(text), button_style.clone(), clr));
+                            }
+                        });
+                });
+        });
+}
@@ -0,0 +1,113 @@
+use super::game::{GameMode, OpponentInformation, PlayerDetailsInfo, PlayerInformation};
+use super::game_states::GameState;
+use super::replays::{ReplayPlayback, ReplayRecorder};
+use super::statistics::MatchTracker;
+use crate::base_systems::turn_based::{ActorTurn, GameTurnSteps};
+use crate::data::saved_match::{
+    ResumedMatch, SAVED_MATCH_VERSION, SavedMatch, SavedMatchProvider, SavedPlayer,
+};
+use crate::data::seed::{MatchSeed, SeededRng};
+use crate::helpers::button::ButtonReleased;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::ai::AiDifficulty;
+use game_core::data::player::PlayerNumber;
+use game_core::r#match::MatchState;
+
+/// Saves the match after every turn, so it can be continued from the menu.
+pub struct ResumePlugin;
+
+impl Plugin for ResumePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameTurnSteps::SearchForAgents),
+            // online matches live on the server and replays are already stored
+            save_match.run_if(
+                not(resource_exists::<ReplayPlayback>).and(not(resource_equals(GameMode::Online))),
+            ),
+        )
+        .add_systems(OnExit(GameState::Game), forget_resumed_match);
+    }
+}
+
+#[derive(SystemParam)]
+struct MatchToSave<'w, 's> {
+    state: Option<Res<'w, MatchState>>,
+    players: Query<
+        'w,
+        's,
+        (
+            &'static PlayerNumber,
+            &'static PlayerDetailsInfo,
+            &'static ActorTurn,
+        ),
+    >,
+    mode: Res<'w, GameMode>,
+    difficulty: Res<'w, AiDifficulty>,
+    player: Res<'w, PlayerInformation>,
+    opponent: Res<'w, OpponentInformation>,
+    ai_rng: Res<'w, SeededRng>,
+    replay: Option<Res<'w, ReplayRecorder>>,
+    tracker: Option<Res<'w, MatchTracker>>,
+}
+
+fn save_match(data: MatchToSave, mut pkv: ResMut<PkvStore>) {
+    let Some(state) = data.state else {
+        return;
+    };
+    if state.is_finished() {
+        pkv.clear_saved_match();
+        return;
+    }
+    if data.players.is_empty() {
+        return;
+    }
+    pkv.save_match(&SavedMatch {
+        version: SAVED_MATCH_VERSION,
+        state: state.clone(),
+        players: data
+            .players
+            .iter()
+            .map(|(nr, details, turn)| SavedPlayer {
+                nr: *nr,
+                details: details.clone(),
+                turn: turn.0,
+            })
+            .collect(),
+        mode: *data.mode,
+        difficulty: *data.difficulty,
+        player: data.player.clone(),
+        opponent: data.opponent.0.clone(),
+        ai_rng: data.ai_rng.0.clone(),
+        replay: data.replay.map(|r| r.0.clone()),
+        tracker: data.tracker.map(|t| t.clone()),
+    });
+}
+
+fn forget_resumed_match(mut commands: Commands) {
+    commands.remove_resource::<ResumedMatch>();
+}
+
+/// Restores the saved match, its turn continues from [`GameTurnSteps::SearchForAgents`].
+pub fn continue_match(
+    _: Trigger<ButtonReleased>,
+    pkv: Res<PkvStore>,
+    mut seed: ResMut<MatchSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    let Some(saved) = pkv.get_saved_match() else {
+        warn!("There is no match to continue");
+        return;
+    };
+    seed.seed = saved.state.seed;
+    commands.insert_resource(saved.player.clone());
+    commands.insert_resource(OpponentInformation(saved.opponent.clone()));
+    commands.insert_resource(saved.state.rules.clone());
+    commands.insert_resource(saved.mode);
+    commands.insert_resource(saved.difficulty);
+    commands.insert_resource(SeededRng(saved.ai_rng.clone()));
+    commands.insert_resource(ResumedMatch(saved));
+    next_state.set(GameState::Game);
+}
@@ -0,0 +1,280 @@
+use super::consts;
+use super::game::{
+    GameMode, HumanPlayer, MatchUpdate, PlayerDetailsInfo, PlayerNumber, TimeSinceTurnStarted,
+};
+use super::game_states::GameState;
+use super::replays::ReplayPlayback;
+use crate::base_systems::turn_based::GameTurnSteps;
+use crate::components::ObserverExtension;
+use crate::data::match_history::{HistorySummary, MatchRecord, PlayedCard};
+use crate::data::profile::{Profile, ProfileProvider};
+use crate::data::saved_match::ResumedMatch;
+use crate::helpers::button::ButtonReleased;
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use game_core::r#match::{MatchEvent, MatchOutcome, MatchState};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const FAVOURITE_CARDS: usize = 3;
+const RECENT_MATCHES: usize = 6;
+
+pub struct StatisticsPlugin;
+
+/// Cards and turn times of the match that is being played,
+/// stored in the active profile once the match ends.
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MatchTracker {
+    cards_used: [Vec<PlayedCard>; 2],
+    cards_discarded: [Vec<PlayedCard>; 2],
+    duration: Duration,
+}
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Statistics), setup_ui)
+            .add_systems(
+                OnEnter(GameState::Game),
+                // hot seat matches are played by two people sharing the profile
+                start_tracking
+                    .run_if(
+                        not(resource_exists::<ReplayPlayback>)
+                            .and(not(resource_equals(GameMode::HotSeat))),
+                    )
+                    .after(super::game::init_players),
+            )
+            .add_systems(
+                OnEnter(GameTurnSteps::ActionSelection),
+                track_turn_time
+                    .run_if(resource_exists::<MatchTracker>)
+                    .before(super::game::switch_player),
+            )
+            .add_systems(
+                Update,
+                track_match
+                    .run_if(resource_exists::<MatchTracker>)
+                    .after(super::game::perform_action),
+            )
+            .add_systems(OnExit(GameState::Game), stop_tracking);
+    }
+}
+
+fn start_tracking(
+    mut commands: Commands,
+    mut timer: ResMut<TimeSinceTurnStarted>,
+    resumed: Option<Res<ResumedMatch>>,
+) {
+    timer.0.reset();
+    // resumed matches continue counting from the saved tracker
+    let tracker = resumed.and_then(|r| r.tracker.clone()).unwrap_or_default();
+    commands.insert_resource(tracker);
+}
+
+fn stop_tracking(mut commands: Commands) {
+    commands.remove_resource::<MatchTracker>();
+}
+
+fn track_turn_time(mut tracker: ResMut<MatchTracker>, timer: Res<TimeSinceTurnStarted>) {
+    tracker.duration += timer.0.elapsed();
+}
+
+fn track_match(
+    mut events: EventReader<MatchUpdate>,
+    mut tracker: ResMut<MatchTracker>,
+    timer: Res<TimeSinceTurnStarted>,
+    match_state: Res<MatchState>,
+    players: Query<(&PlayerNumber, &PlayerDetailsInfo, Has<HumanPlayer>)>,
+    mut pkv: ResMut<PkvStore>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        match &**event {
+            MatchEvent::CardUsed { player, card } => {
+                tracker.cards_used[player.index()].push(card.into())
+            }
+            MatchEvent::CardDropped { player, card } => {
+                tracker.cards_discarded[player.index()].push(card.into())
+            }
+            MatchEvent::MatchEnded(outcome) => {
+                tracker.duration += timer.0.elapsed();
+                commands.remove_resource::<MatchTracker>();
+                // matches between bots are not played by the profile owner
+                let Some(record) =
+                    finish_tracking(&mut tracker, outcome, match_state.turn, &players)
+                else {
+                    continue;
+                };
+                if pkv.update_profile(|p| p.record_match(record)) {
+                    info!("Match stored in the profile history");
+                } else {
+                    warn!("No profile selected, the match is not stored");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn finish_tracking(
+    tracker: &mut MatchTracker,
+    outcome: &MatchOutcome,
+    turn: u32,
+    players: &Query<(&PlayerNumber, &PlayerDetailsInfo, Has<HumanPlayer>)>,
+) -> Option<MatchRecord> {
+    let mut humans = players.iter().filter(|(_, _, human)| *human);
+    let (nr, _, _) = humans.next()?;
+    if humans.next().is_some() {
+        return None;
+    }
+    let (_, opponent, _) = players.iter().find(|(n, _, _)| *n != nr)?;
+    Some(MatchRecord {
+        opponent_name: opponent.name.clone(),
+        opponent_avatar_id: opponent.avatar_id,
+        won: outcome.winner == *nr,
+        reason: outcome.reason,
+        // the turn counter is not increased for the action that ended the match
+        turns: turn + 1,
+        cards_used: std::mem::take(&mut tracker.cards_used[nr.index()]),
+        cards_discarded: std::mem::take(&mut tracker.cards_discarded[nr.index()]),
+        duration: tracker.duration.as_secs_f32(),
+    })
+}
+
+fn back_to_menu(_: Trigger<ButtonReleased>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Menu);
+}
+
+fn format_duration(secs: f32) -> String {
+    let secs = secs as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn summary_lines(profile: &Profile) -> Vec<String> {
+    let summary = HistorySummary::new(&profile.history, FAVOURITE_CARDS);
+    let stats = &profile.stats;
+    let mut lines = vec![match stats.win_rate() {
+        Some(rate) => format!(
+            "Won {} of {} matches ({:.0}%)",
+            stats.matches_won,
+            stats.matches_won + stats.matches_lost,
+            rate
+        ),
+        None => "No matches played yet".to_owned(),
+    }];
+    if summary.current_streak != 0 {
+        let (count, result) = match summary.current_streak {
+            s if s > 0 => (s, "wins"),
+            s => (-s, "losses"),
+        };
+        lines.push(format!(
+            "Current streak: {} {}, best win streak: {}",
+            count, result, summary.best_win_streak
+        ));
+    }
+    if !summary.favourite_cards.is_empty() {
+        let cards: Vec<String> = summary
+            .favourite_cards
+            .iter()
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect();
+        lines.push(format!("Favourite cards: {}", cards.join(", ")));
+    }
+    if !profile.history.is_empty() {
+        let resources: Vec<String> = summary
+            .resources
+            .iter()
+            .map(|r| format!("{} {} cards/{} spent", r.resource, r.cards, r.spent))
+            .collect();
+        lines.push(resources.join(", "));
+        lines.push(format!("Discarded cards: {}", summary.discarded));
+    }
+    lines
+}
+
+fn record_label(record: &MatchRecord) -> String {
+    format!(
+        "{} vs {} - {} in {} turns, {}",
+        if record.won { "Won" } else { "Lost" },
+        record.opponent_name,
+        record.reason_label(),
+        record.turns,
+        format_duration(record.duration)
+    )
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<PkvStore>) {
+    let profile = pkv.get_profile().unwrap_or_default();
+    let header_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(45.0);
+    let button_style =
+        TextFont::from_font(asset_server.load(consts::LABEL_FONT)).with_font_size(25.0);
+    let text_style =
+        TextFont::from_font(asset_server.load(consts::REGULAR_FONT)).with_font_size(20.0);
+    let text_clr = TextColor(Srgba::hex("fcfd9e").unwrap().into());
+    let clr = TextColor(Color::linear_rgb(0.7, 0.7, 0.7));
+
+    commands
+        .spawn(super::root_node())
+        .insert((
+            StateScoped(GameState::Statistics),
+            Name::new("statistics-root"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageNode::new(asset_server.load("img/start_screen_bg.png")),
+                ZIndex(-1),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    width: Val::Vw(100.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Srgba::hex("2c422e").unwrap().into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new(profile.name.clone()), header_style));
+                    for line in summary_lines(&profile) {
+                        parent.spawn((Text::new(line), text_style.clone(), text_clr));
+                    }
+                    for record in profile.history.iter().take(RECENT_MATCHES) {
+                        parent
+                            .spawn(Node {
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(10.0),
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                row.spawn((
+                                    ImageNode::new(asset_server.load(Profile::format_avatar_path(
+                                        record.opponent_avatar_id,
+                                    ))),
+                                    Node {
+                                        width: Val::Px(40.0),
+                                        height: Val::Px(40.0),
+                                        ..default()
+                                    },
+                                ));
+                                row.spawn((
+                                    Text::new(record_label(record)),
+                                    text_style.clone(),
+                                    text_clr,
+                                ));
+                            });
+                    }
+                    parent
+                        .spawn(super::button(&asset_server))
+                        .with_child((Text::new("Back"), button_style.clone(), clr))
+                        .observe_in_child(back_to_menu);
+                });
+        });
+}